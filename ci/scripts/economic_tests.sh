@@ -1,28 +1,15 @@
 #!/bin/bash
-source ./ci/scripts/functions.sh
 
-set_default_env() {
-  SOL_RPC_URL="http://localhost:8899"
-  ETH_RPC_URL="http://localhost:9090"
-}
-
-
-get_solana_balance() {
-  set_default_env
-  SOLANA=$(get_container_id solana)
-  SOL_BALANCE=$(docker exec $SOLANA solana balance $1 -u $SOL_RPC_URL | awk '{print $1}')
-  LAMPORTS=$(printf "%.0f" "$(echo "$SOL_BALANCE * 1000000000" | bc)")
-  echo "$LAMPORTS"
-}
-
-get_eth_balance() {
-  set_default_env
-  RESPONSE=$(curl -s -X POST --data '{"jsonrpc":"2.0","method":"eth_getBalance","params":["'"$1"'", "latest"],"id":1}' -H "Content-Type: application/json" "$ETH_RPC_URL")
-  # Extract the result field (hex string), convert to decimal
-  BAL_HEX=$(echo "$RESPONSE" | jq -r .result)
-  # Convert hex to decimal using `bc`
-  BAL_DEC=$(echo "$BAL_HEX" | awk '{print strtonum($1)}')
-  # Convert RSOL to lamports
-  LAMPORTS=$(printf "%.0f" "$(echo "scale=18; $BAL_DEC / 1000000000" | bc)")
-  echo "$LAMPORTS"
+# Balance of the operator on Solana and of its fee recipient on the rollup, compared by the round_trip binary.
+# Usage: operator_balances before|after <operator solana key> <fee recipient address>
+# The balances taken before the run are kept in records/operator_balances.json
+operator_balances() {
+  docker run --rm --network="ci_net" \
+    -v "$(pwd)/../records:/opt/records" \
+    -e PROXY_URL=$PROXY_URL \
+    -e GETH_URL=$GETH_URL \
+    -e OPERATOR=$2 \
+    -e FEE_RECIPIENT=$3 \
+    --entrypoint /opt/bin/round_trip \
+    romelabs/tests:${TESTS_TAG:-latest} operator_balances_$1 --exact --ignored --nocapture
 }
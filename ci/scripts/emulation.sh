@@ -0,0 +1,15 @@
+#!/bin/bash
+# Emulation reports compared with the real execution on the same stack as evm.sh
+source ./ci/scripts/functions.sh
+
+filename=$(basename -- "$0") 
+TEST_NAME="${filename%.*}"
+echo "Test name: $TEST_NAME"
+$(create_log_file $TEST_NAME)
+
+cd ./ci
+docker-compose up --quiet-pull -d solana
+sleep 5
+airdrop
+
+run_tests_container -e TEST_ACCOUNT=$TEST_ACCOUNT
@@ -174,3 +174,25 @@ airdrop_oz() {
   docker exec $SOLANA solana -u http://localhost:8899 airdrop 10000
   docker exec $SOLANA solana -u http://localhost:8899 airdrop 10000 ./id1.json
 }
+
+# Run the test binary of TEST_NAME in the tests container and exit with error if any test failed.
+# The arguments are passed to docker run, e.g. -e TEST_ACCOUNT=$TEST_ACCOUNT
+run_tests_container() {
+  echo "Starting tests..."
+  docker run --network="ci_net" \
+    -e PROXY_URL=$PROXY_URL \
+    -e GETH_URL=$GETH_URL \
+    -e TEST_NAME=$TEST_NAME \
+    "$@" \
+    --name="tests" \
+    romelabs/tests:${TESTS_TAG:-latest} | tee ../records/$TEST_NAME.txt
+
+  if ! grep -q '; 0 failed;' ../records/$TEST_NAME.txt; then
+    echo "Tests failed. Exiting with error."
+    clear_env
+    exit 1
+  else
+    echo "Tests passed. Stopping tests..."
+    clear_env
+  fi
+}
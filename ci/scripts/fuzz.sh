@@ -0,0 +1,23 @@
+#!/bin/bash
+# Nightly fuzzing of the proxy against op-geth, the budget of every fuzz test is set by FUZZ_CASES and FUZZ_DURATION
+source ./ci/scripts/functions.sh
+
+filename=$(basename -- "$0") 
+TEST_NAME="${filename%.*}"
+echo "Test name: $TEST_NAME"
+$(create_log_file $TEST_NAME)
+
+cd ./ci
+docker-compose up --quiet-pull -d solana proxy hercules rhea geth faucet postgres > /dev/null
+sleep 5
+airdrop
+regrollup
+deposit
+
+# two fuzz tests within the 20 minutes of the job
+run_tests_container \
+  -e EXTENDED_LOGS=$EXTENDED_LOGS \
+  -e POOLING_INTERVAL=2 \
+  -e FUZZ_CASES=${FUZZ_CASES:-1000} \
+  -e FUZZ_DURATION=${FUZZ_DURATION:-300} \
+  -e FUZZ_SEED=$FUZZ_SEED
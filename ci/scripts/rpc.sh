@@ -0,0 +1,17 @@
+#!/bin/bash
+# JSON-RPC compatibility matrix of the proxy against op-geth
+source ./ci/scripts/functions.sh
+
+filename=$(basename -- "$0") 
+TEST_NAME="${filename%.*}"
+echo "Test name: $TEST_NAME"
+$(create_log_file $TEST_NAME)
+
+cd ./ci
+docker-compose up --quiet-pull -d solana proxy hercules rhea geth faucet postgres > /dev/null
+sleep 5
+airdrop
+regrollup
+deposit
+
+run_tests_container -e EXTENDED_LOGS=$EXTENDED_LOGS -e POOLING_INTERVAL=2
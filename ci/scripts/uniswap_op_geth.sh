@@ -26,8 +26,7 @@ airdrop_to_wallets
 OPERATOR_SOL=$(get_key_of ./rhea-sender.json)
 echo "[ Info ] - Operator SOL key: $OPERATOR_SOL"
 FEE_RECIPIENT_RSOL=0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5
-OPERATOR_BAL_BEFORE=$(get_solana_balance "$OPERATOR_SOL")
-FEE_RECIPIENT_BAL_BEFORE=$(get_eth_balance "$FEE_RECIPIENT_RSOL")
+operator_balances before "$OPERATOR_SOL" "$FEE_RECIPIENT_RSOL"
 # OPERATOR_SOL=4X2seLbitEeXQXwQveTz3hFX4wNMYqgaRgB37VzS9QZY
 # FEE_RECIPIENT_RSOL=0x95222290DD7278Aa3Ddd389Cc1E1d165CC4BAfe5
 
@@ -57,29 +56,7 @@ else
     yarn test | tee ../records/$TEST_NAME.txt
 fi
 
-OPERATOR_BAL_AFTER=$(get_solana_balance "$OPERATOR_SOL")
-FEE_RECIPIENT_BAL_AFTER=$(get_eth_balance "$FEE_RECIPIENT_RSOL")
-echo "========================= ECONOMIC TESTS ========================="
-echo "[ Info ] - Balances in lamports"
-echo "[ Info ] - OPERATOR_BAL_BEFORE_: $OPERATOR_BAL_BEFORE"
-echo "[ Info ] - OPERATOR_BAL_AFTER__: $OPERATOR_BAL_AFTER"
-echo "[ Info ] - FEE_RECIPIENT_BEFORE: $FEE_RECIPIENT_BAL_BEFORE"
-echo "[ Info ] - FEE_RECIPIENT_AFTER_: $FEE_RECIPIENT_BAL_AFTER"
-echo "[ Info ] - Before - After is: OPERATOR_BAL_BEFORE + FEE_RECIPIENT_BAL_BEFORE - OPERATOR_BAL_AFTER - FEE_RECIPIENT_BAL_AFTER:"
-echo "[ Info ] - Before - After => $(echo "$OPERATOR_BAL_BEFORE + $FEE_RECIPIENT_BAL_BEFORE - $OPERATOR_BAL_AFTER - $FEE_RECIPIENT_BAL_AFTER" | bc) "
-OPERATOR_BAL_CHANGE=$(echo "$OPERATOR_BAL_BEFORE - $OPERATOR_BAL_AFTER" | bc)
-FEE_RECIPIENT_BAL_CHANGE=$(echo "$FEE_RECIPIENT_BAL_AFTER - $FEE_RECIPIENT_BAL_BEFORE" | bc)
-echo "[ Info ] - $OPERATOR_BAL_CHANGE < OPERATOR | OPERATOR_BAL_BEFORE - OPERATOR_BAL_AFTER"
-echo "[ Info ] - $FEE_RECIPIENT_BAL_CHANGE < FEE_RECIPIENT | FEE_RECIPIENT_BAL_AFTER - FEE_RECIPIENT_BAL_BEFORE"
-if [ "$(echo "$OPERATOR_BAL_CHANGE == $FEE_RECIPIENT_BAL_CHANGE" | bc -l)" -eq 1 ]; then
-    echo "[ Info ] - Balances are equal"
-    echo "========================= -------------- ========================="
-else
-    echo "[ Warning ] - Balances are different!"
-    echo "========================= -------------- ========================="
-    # clear_env
-    # exit 1
-fi
+operator_balances after "$OPERATOR_SOL" "$FEE_RECIPIENT_RSOL"
 
 if cat ../records/$TEST_NAME$SUIT.txt | grep -E '[1-9][0-9]? failing'; then
   echo "Tests failed. Exiting with error."
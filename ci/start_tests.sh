@@ -16,7 +16,7 @@ fi
 
 if [ -n "$CI_ENV" ]; then
   echo "Running in real testnet"
-  CI_ARG="--skip iter_rw_atomic_ro"
+  CI_ARG=""
 fi
 
 echo "PRINTENV SH:"
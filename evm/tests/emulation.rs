@@ -0,0 +1,109 @@
+mod shared;
+
+use ethers_core::types::{H256, U256};
+use ethers_signers::Signer as EthSigner;
+use rstest::*;
+use shared::{
+    emulation::emulate_tx,
+    fixture::client,
+    tx::{abi, do_tx, method_id},
+    wallet, CONTRACTS,
+};
+use std::collections::BTreeMap;
+
+/// The emulation report is compared with the real execution of the same transaction.
+///  slots: storage slots of the contract touched by the transaction, (slot, is written)
+///  logs: number of Set events
+#[rstest(
+    contract,
+    method,
+    slots,
+    return_value,
+    logs,
+    atomic,
+    case::get("EmulationProbe", "get", vec![(0, false)], 7, 0, true),
+    case::set("EmulationProbe", "set(uint256 10)", vec![(0, true)], 10, 1, true),
+    case::iterate("EmulationProbe", "iterate", vec![(1, true)], 200, 1, false),
+    case::get_storage_at("GetStorageAt", "get", vec![(0, false)], 7, 0, true),
+)]
+#[serial_test::serial]
+async fn emulation_report(
+    contract: String,
+    method: &str,
+    slots: Vec<(u64, bool)>,
+    return_value: u64,
+    logs: usize,
+    atomic: bool,
+) {
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let address = client.deploy(&contract, &wallet, None, 2).await;
+
+    let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+    let tx = do_tx(&client, Some(address), method_id(&abi, method), &wallet, 0.into(), 2);
+    let emulation = emulate_tx(&client, &tx, &wallet).await;
+    println!("emulation of {}.{}: {:?}", contract, method, emulation);
+
+    // storage
+    let expected: BTreeMap<U256, bool> = slots
+        .iter()
+        .map(|(slot, rw)| (U256::from(*slot), *rw))
+        .collect();
+    assert_eq!(emulation.slots(&address), expected);
+
+    // return data
+    let call = client.call(&tx.clone().into()).unwrap();
+    assert_eq!(emulation.return_data, call.to_vec());
+    if atomic {
+        assert_eq!(U256::from_big_endian(&emulation.return_data), return_value.into());
+    }
+
+    // logs
+    let signature = H256::from(ethers::utils::keccak256("Set(address,uint256)"));
+    assert_eq!(emulation.logs.len(), logs);
+    for log in &emulation.logs {
+        assert_eq!(log.address, address);
+        assert_eq!(log.topics, vec![signature, H256::from(wallet.address())]);
+        assert_eq!(U256::from_big_endian(&log.data), return_value.into());
+    }
+
+    // gas
+    let estimate = *tx.gas().unwrap();
+    assert!(emulation.gas > U256::zero());
+    assert!(emulation.gas <= estimate);
+    assert!(emulation.steps > 0);
+    assert_eq!(emulation.is_atomic, atomic);
+
+    // real execution
+    let gas_price = client.gas_price().unwrap();
+    let before = client.get_balance(wallet.address()).unwrap();
+    let report = client.send_tx_with_report(&tx, &wallet).await;
+    let after = client.get_balance(wallet.address()).unwrap();
+
+    assert_eq!(report.is_atomic(), emulation.is_atomic);
+    assert!(before - after <= emulation.gas * gas_price);
+
+    // every account of the emulation is passed to rome-evm with the same access
+    let mut accounts: BTreeMap<_, bool> = BTreeMap::new();
+    for tx in &report.solana_txs {
+        for (key, writable) in &tx.accounts {
+            *accounts.entry(*key).or_default() |= *writable;
+        }
+    }
+    for (key, writable) in &emulation.accounts {
+        let actual = accounts.get(key);
+        assert!(actual.is_some(), "account {} is not passed to rome-evm", key);
+        if *writable {
+            assert_eq!(actual, Some(&true), "account {} must be writable", key);
+        }
+    }
+
+    // written slots are changed by the real execution
+    for (slot, rw) in &expected {
+        let value = client.eth_get_storage_at(address, *slot).unwrap();
+        if *rw {
+            assert_eq!(value, return_value.into());
+        }
+    }
+}
@@ -5,17 +5,29 @@ use primitive_types::H160;
 use rome_sdk::rome_evm_client::emulator::Instruction;
 use rstest::*;
 use shared::{
-    fixture::client,
-    tx::{abi, do_rlp, do_tx, do_tx_base, method_id},
-    utils::{retry_panic}, 
+    bytecode::{init_code, payload, runtime_code, MAX_CODE_SIZE, RETURN_VALUE},
+    client::Client,
+    emulation::emulate_tx,
+    fees::{payer_fee_recipients, send_with_fee, FeeDistribution},
+    fixture::{client, mode_client, Deposit, Malformation},
+    fuzz::{fuzz_tx, generate, FuzzBudget},
+    gas::{abi_calls, measure, GasBand, GasTable},
+    ledger::{to_lamports, to_wei},
+    lock::{run_conflict, LockMatrix, LockRow, Outcome as LockOutcome, WindowMissed},
+    parity::ModeFees,
+    precompile::{load_vectors, probe_gas, vector},
+    resources::ExecutionMode,
+    retry::{retry_classified, Backoff, ErrorClass, RetryPolicy},
+    tx::{abi, calc_address, calc_create2_address, do_rlp, do_tx, do_tx_with_gas, method_id},
+    utils::{run_on_devnet, run_on_testnet},
+    validation::{invalid_rlp, Invalid, Target},
     wallet, CONTRACTS, WITHDRAWAL_ADDRESS, test_account,
 };
-use ethers_core::{
-    k256::ecdsa::SigningKey, types::U256,
-};
-use ethers_signers::{Signer as EthSigner, Wallet};
-use solana_sdk::signer::Signer;
+use ethers_core::types::{H256, U256};
+use ethers_signers::Signer as EthSigner;
+use solana_sdk::{signature::Keypair, signer::Signer};
 use std::str::FromStr;
+use std::time::Instant;
 use ethers::types::Address;
 
 
@@ -30,7 +42,7 @@ use ethers::types::Address;
 )]
 async fn evm_deploy(contract: String, tx_type: Vec<u8>, zero_gas: bool) {
     let wallet = wallet();
-    let client = client(zero_gas);
+    let client = mode_client(zero_gas);
     client.airdrop(wallet.address(), U256::exp10(19)).await;
 
     for typ in tx_type {
@@ -41,7 +53,6 @@ async fn evm_deploy(contract: String, tx_type: Vec<u8>, zero_gas: bool) {
 #[rstest(
     contract,
     methods,
-    zero_gas,
     case::cu(
         "CU",
         vec![
@@ -49,7 +60,6 @@ async fn evm_deploy(contract: String, tx_type: Vec<u8>, zero_gas: bool) {
             "update_single",
             "push",
         ],
-        false
     ),
     case::tstore(
         "TestTransientStorage",
@@ -58,7 +68,6 @@ async fn evm_deploy(contract: String, tx_type: Vec<u8>, zero_gas: bool) {
             "callTransientStorage(uint256 2)",
             "callTransientStorage(uint256 5)",
         ],
-        false
     ),
     case::selfdestruct(
         "DestructCaller",
@@ -67,7 +76,6 @@ async fn evm_deploy(contract: String, tx_type: Vec<u8>, zero_gas: bool) {
             "deploy_and_destruct",
             "check",
         ],
-        false
     ),
     case::revert(
         "RevertFactory",
@@ -78,24 +86,26 @@ async fn evm_deploy(contract: String, tx_type: Vec<u8>, zero_gas: bool) {
             "case_3",
             "case_4",
         ],
-        false,
     ),
+    zero_gas => [true, false],
 )]
 async fn evm_call_unchecked(
     contract: String,
     methods: Vec<&str>,
     zero_gas: bool,
 ) {
-    let client = client(zero_gas);
+    let client = mode_client(zero_gas);
 
     let wallet = wallet();
     client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let fees = ModeFees::take(&client, wallet.address(), zero_gas).await;
     // deploy contract
     let address = client.deploy(&contract, &wallet, None, 2).await;
     // update storage
     for method in methods {
-        client.method_call(&contract, &address, method, &wallet, 0.into(), 2).await
+        client.method_call(&contract, &address, method, &wallet, 0.into(), 2).await;
     }
+    fees.check(&client).await;
 }
 
 #[rstest(
@@ -103,7 +113,6 @@ async fn evm_call_unchecked(
     methods,
     eth_calls,
     results,
-    zero_gas,
     case::touch_storage(
         "TouchStorage",
         vec![
@@ -127,8 +136,8 @@ async fn evm_call_unchecked(
             "0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000568656c6c6f000000000000000000000000000000000000000000000000000000",
             "0000000000000000000000000000000000000000000000000000000000000005",
         ],
-        false
     ),
+    zero_gas => [true, false],
 )]
 async fn evm_call(
     contract: String,
@@ -137,16 +146,18 @@ async fn evm_call(
     results: Vec<&str>,
     zero_gas: bool,
 ) {
-    let client = client(zero_gas);
+    let client = mode_client(zero_gas);
 
     let wallet = wallet();
     client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let fees = ModeFees::take(&client, wallet.address(), zero_gas).await;
     // deploy contract
     let address = client.deploy(&contract, &wallet, None, 2).await;
     // update storage
     for method in methods {
-        client.method_call(&contract, &address, method, &wallet, 0.into(), 2).await
+        client.method_call(&contract, &address, method, &wallet, 0.into(), 2).await;
     }
+    fees.check(&client).await;
     // call eth_calls to check the results
     for (eth_call, expected_hex) in eth_calls.iter().zip(results) {
         let result = client.eth_call(&contract, &address, eth_call, &wallet);
@@ -162,7 +173,6 @@ async fn evm_call(
     methods,
     eth_calls,
     results,
-    zero_gas,
     case::touch_storage(
         "TouchStorage",
         "NestedCall",
@@ -189,8 +199,8 @@ async fn evm_call(
             "0000000000000000000000000000000000000000000000000000000000000005",
             "0000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000c48656c6c6f5f776f726c64210000000000000000000000000000000000000000"
         ],
-        false,
     ),
+    zero_gas => [true, false],
 )]
 async fn evm_nested_call(
     contract: String,
@@ -200,10 +210,11 @@ async fn evm_nested_call(
     results: Vec<&str>,
     zero_gas: bool,
 ) {
-    let client = client(zero_gas);
+    let client = mode_client(zero_gas);
 
     let wallet = wallet();
     client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let fees = ModeFees::take(&client, wallet.address(), zero_gas).await;
     // deploy contract
     let contract_addr = client.deploy(&contract, &wallet, None, 2).await;
     // deploy caller
@@ -211,8 +222,9 @@ async fn evm_nested_call(
     let caller_addr = client.deploy(&caller, &wallet, Some(ctor), 2).await;
     // call nested contract's methods
     for method in methods {
-        client.method_call(&caller, &caller_addr, method, &wallet, 0.into(), 2).await
+        client.method_call(&caller, &caller_addr, method, &wallet, 0.into(), 2).await;
     }
+    fees.check(&client).await;
     // call eth_calls to check the results
     for (eth_call, expected_hex) in eth_calls.iter().zip(results) {
         let result = client.eth_call(&caller, &caller_addr, eth_call, &wallet);
@@ -224,14 +236,26 @@ async fn evm_nested_call(
 #[rstest(
     contract,
     methods,
+    modes,
     tx_type,
-    case::storage("AtomicIterative", vec!["atomic_rw", "iterative_rw"], 0),
-    case::storage("AtomicIterative", vec!["atomic_ro", "iterative_ro"], 2),
+    case::storage(
+        "AtomicIterative",
+        vec!["atomic_rw", "iterative_rw"],
+        vec![ExecutionMode::Atomic, ExecutionMode::Iterative],
+        0
+    ),
+    case::storage(
+        "AtomicIterative",
+        vec!["atomic_ro", "iterative_ro"],
+        vec![ExecutionMode::Atomic, ExecutionMode::Iterative],
+        2
+    ),
 )]
 #[serial_test::serial]
 async fn evm_gas_transfer(
     contract: String,
     methods: Vec<&str>,
+    modes: Vec<ExecutionMode>,
     tx_type: u8
 ) {
     let wallet = wallet();
@@ -243,12 +267,13 @@ async fn evm_gas_transfer(
     let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
 
     // call methods and compare the estimate gas with gas_transfer
-    for method in methods {
+    for (method, mode) in methods.into_iter().zip(modes) {
         let tx = do_tx(&client, Some(address), method_id(&abi, method), &wallet, 0.into(), tx_type);
 
         let before = client.get_balance(wallet.address()).unwrap();
-        client.method_call(&contract, &address, method, &wallet, 0.into(), tx_type).await;
+        let report = client.method_call_with_report(&contract, &address, method, &wallet, 0.into(), tx_type).await;
         let after = client.get_balance(wallet.address()).unwrap();
+        report.assert_mode(mode);
 
         let estimage_gas = tx.gas().unwrap().clone();
         let gas_transfer = before.checked_sub(after).unwrap();
@@ -263,6 +288,78 @@ async fn evm_gas_transfer(
 
 }
 
+/// Every method of the contract is sent with the estimated gas limit.
+/// The estimate / actual gas ratio must be inside the band, see GasBand::from_env.
+/// The table is appended to GAS_REPORT file. The gas used is charged only on the fee-paying rollup
+#[rstest(
+    contract,
+    case::hello_world("HelloWorld"),
+    case::touch_storage("TouchStorage"),
+    case::multi_storage("MultiStorage"),
+    case::atomic_iterative("AtomicIterative"),
+    case::get_storage_at("GetStorageAt"),
+    case::cu("CU"),
+    case::transient_probe("TransientProbe"),
+)]
+#[serial_test::serial]
+async fn evm_gas_accuracy(contract: String) {
+    let wallet = wallet();
+    let client = client(false);
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let band = GasBand::from_env();
+    let mut table = GasTable::default();
+
+    let address = client.deploy(&contract, &wallet, None, 0).await;
+    let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+
+    for (method, data) in abi_calls(&abi) {
+        let record = measure(&client, &contract, address, &method, data, &wallet, &band).await;
+        table.push(record);
+    }
+
+    table.write();
+    let failures = table.failures();
+    assert!(failures.is_empty(), "gas estimate is out of {:?}: {:#?}", band, failures);
+}
+
+/// Every EVM transaction is paid by one payer on Solana and its fee goes to one fee recipient
+/// of this payer. The payers and the fee recipients are checked to be chosen uniformly, see FeeDistribution::check
+#[rstest(
+    contract,
+    method,
+    count,
+    zero_gas,
+    case::transfer("", "", 20, false),
+    case::call("TouchStorage", "set_value(uint256 10)", 20, false),
+    case::call_fee_free("TouchStorage", "set_value(uint256 10)", 5, true),
+)]
+#[serial_test::serial]
+async fn evm_fee_distribution(contract: String, method: &str, count: usize, zero_gas: bool) {
+    let client = mode_client(zero_gas);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+
+    let (to, data, value) = if contract.is_empty() {
+        (shared::wallet().address(), vec![], U256::exp10(9))
+    } else {
+        let address = client.deploy(&contract, &wallet, None, 2).await;
+        let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+        (address, method_id(&abi, method), U256::zero())
+    };
+
+    let fee_recipients = payer_fee_recipients(zero_gas).await;
+    let mut distribution = FeeDistribution::default();
+    for _ in 0..count {
+        let tx = do_tx(&client, Some(to), data.clone(), &wallet, value, 2);
+        let record = send_with_fee(&client, &tx, &wallet, &fee_recipients).await;
+        println!("fee record: {:?}", record);
+        record.check(&fee_recipients);
+        distribution.push(&record);
+    }
+
+    distribution.check(&fee_recipients);
+}
+
 #[rstest(
     contract,
     method,
@@ -314,6 +411,8 @@ async fn evm_get_storage_at(
     assert_eq!(slot_value, 7.into());
 }
 
+/// The second transaction is sent between the iterations of the first one.
+/// Expected outcome of the second transaction:
 ///  case1: Iterative tx writes to storage account. Atomic tx writes to the locked account. Error
 ///  case2: Iterative tx writes to storage account. Atomic tx reads the locked account. Ok
 ///  case3: Iterative tx writes to storage account. Iterative tx writes to the locked account. Error
@@ -323,83 +422,65 @@ async fn evm_get_storage_at(
 ///  case6: Iterative tx reads storage account. Atomic tx writes to the locked account. Error
 ///  case7: Iterative tx reads storage account. Iterative tx writes to the locked account. Error
 ///  case8: Iterative tx reads storage account. Iterative tx reads the locked account. Result: Ok
-#[rstest(
-    contract,
-    first,
-    first_count,
-    second,
-    second_count,
-    tx_type,
-    case::iter_rw_atomic_ro("AtomicIterative", "iterative_rw", 5, "atomic_ro", 20, 2),
-#[should_panic]
-    case::iter_rw_atomic_rw("AtomicIterative", "iterative_rw", 5, "atomic_rw", 20, 2),
-#[should_panic]
-    case::iter_rw_iter_rw("AtomicIterative", "iterative_rw", 5, "iterative_rw", 5, 2),
-// #[should_panic]
-//     case::iter_rw_iter_ro("AtomicIterative", "iterative_rw", 5, "iterative_ro", 5, 2),
-//     case::iter_ro_atomic_ro("AtomicIterative", "iterative_ro", 5, "atomic_ro",  20, 2),
-// #[should_panic]
-//     case::iter_ro_atomic_rw("AtomicIterative", "iterative_ro", 5, "atomic_rw",  20, 2),
-// #[should_panic]
-//     case::iter_ro_iter_rw("AtomicIterative", "iterative_ro", 5, "iterative_rw", 5, 2),
-//     case::iter_ro_iter_ro("AtomicIterative", "iterative_ro", 5, "iterative_ro", 5, 2),
-)]
+///
+/// Error: the transaction is rejected while the accounts are locked and executed after the lock is released.
+/// All cases are run, the matrix of the outcomes is written before the check
+#[rstest(contract, tx_type, case("AtomicIterative", 2))]
 #[serial_test::serial]
-async fn evm_account_lock(
-    contract: String,
-    first: &str,
-    first_count: u64,
-    second: &str,
-    second_count: u64,
-    tx_type: u8
-) {
-    let _result: Result<(), ()> = retry_panic(|| async {
-        let wallet1 = wallet();
-        let wallet2 = wallet();
-        let zero_gas = true;
-
-        let client_zero_gas = client(zero_gas);
-        // deploy contract
-        let address = client_zero_gas.deploy(&contract, &wallet1, None, tx_type).await;
-
-        // preparing the method calls
-        let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+async fn evm_account_lock(contract: String, tx_type: u8) {
+    let cases = [
+        ("iterative_rw", "atomic_rw", LockOutcome::Retried),
+        ("iterative_rw", "atomic_ro", LockOutcome::Ok),
+        ("iterative_rw", "iterative_rw", LockOutcome::Retried),
+        ("iterative_rw", "iterative_ro", LockOutcome::Retried),
+        ("iterative_ro", "atomic_ro", LockOutcome::Ok),
+        ("iterative_ro", "atomic_rw", LockOutcome::Retried),
+        ("iterative_ro", "iterative_rw", LockOutcome::Retried),
+        ("iterative_ro", "iterative_ro", LockOutcome::Ok),
+    ];
 
-        let f = |method: &str, count: u64, wallet: &Wallet<SigningKey>, | -> Vec<Vec<u8>> {
-            let nonce = client_zero_gas.transaction_count(wallet.address()).unwrap().as_u64();
-            let mut rlp = vec![];
-            for i in 0..count {
-                let tx = do_tx_base(&client_zero_gas, Some(address), method_id(&abi, method), &wallet, 0.into(), tx_type, nonce + i);
-                rlp.push(do_rlp(&tx, &wallet).to_vec()) ;
-            }
-            rlp
-        };
-
-        let rlp_first = f(first, first_count, &wallet1);
-        let rlp_second = f(second, second_count, &wallet2);
+    let zero_gas = true;
+    let client_zero_gas = client(zero_gas);
+    let wallet = wallet();
 
-        let client1 = client_zero_gas.clone();
-        let client2 = client_zero_gas.clone();
+    // deploy contract
+    let address = client_zero_gas.deploy(&contract, &wallet, None, tx_type).await;
 
-        let tx1_jh = tokio::spawn(
-            async move {
-                for rlp in rlp_first {
-                    client1.send_transaction(rlp.into()).await.unwrap();
-                }
-            });
+    // the first transaction must hold the lock between the iterations
+    for first in ["iterative_rw", "iterative_ro"] {
+        let report = client_zero_gas.method_call_with_report(&contract, &address, first, &wallet, 0.into(), tx_type).await;
+        assert!(report.mode().is_iterative());
+    }
 
-        let tx2_jh = tokio::spawn(
-            async move {
-                for rlp in rlp_second {
-                    client2.send_transaction(rlp.into()).await.unwrap();
-                }
-            });
+    let mut matrix = LockMatrix::default();
+    for (first, second, expected) in cases {
+        // skipped on the real testnet, see ci/start_tests.sh
+        if std::env::var("CI_ENV").map(|v| !v.is_empty()).unwrap_or(false) && (first, second) == ("iterative_rw", "atomic_ro") {
+            continue;
+        }
+        // the scenario is repeated only if the transactions did not overlap
+        let name = format!("evm_account_lock::{}_{}", first, second);
+        let policy = RetryPolicy::new(3, Backoff::None);
+        let conflict = retry_classified(
+            &name,
+            &policy,
+            |_: &WindowMissed| ErrorClass::Transient,
+            || run_conflict(client_zero_gas.clone(), &contract, address, first, second, tx_type),
+        )
+        .await
+        .ok();
 
-        let (tx1_res, tx2_res) = tokio::join!(tx1_jh, tx2_jh);
+        matrix.rows.push(LockRow {
+            first: first.to_string(),
+            second: second.to_string(),
+            expected,
+            conflict,
+        });
+    }
+    matrix.write();
 
-        tx1_res.map_err(|e| println!("{:?}", e)).unwrap();
-        tx2_res.map_err(|e| println!("{:?}", e)).unwrap();
-    }).await;
+    let failed = matrix.failed();
+    assert!(failed.is_empty(), "unexpected lock outcomes: {:?}", failed);
 }
 
 #[rstest(
@@ -505,7 +586,7 @@ async fn withdraw_raw(
  
     // withdrawal
     for method in methods {
-        client.raw_call(&address_predeployed, method, &sender, U256::from(amount), 2, solana_address_bytes32).await
+        client.raw_call(&address_predeployed, method, &sender, U256::from(amount), 2, solana_address_bytes32).await;
     }
 
     // verification
@@ -520,3 +601,810 @@ async fn withdraw_raw(
     assert_eq!(solana_balance_after, solana_balance_before + amount  / 1_000_000_000 ); // Divide by 10^9 to convert wei to lamports
     assert_eq!(sender_balance_after, sender_balance_before - amount - fee_diff); 
 }
+
+#[rstest(
+    mint,
+    value,
+    to_self,
+    case::mint_eq_value(Some(U256::exp10(18)), U256::exp10(18), false),
+    case::mint_gt_value(Some(U256::exp10(18) * 3), U256::exp10(18), false),
+    case::mint_only(Some(U256::exp10(18)), U256::zero(), false),
+    case::mint_to_self(Some(U256::exp10(18)), U256::exp10(18), true),
+    // the transfer fails, but the minted amount stays with the sender
+    case::mint_lt_value(Some(U256::exp10(17)), U256::exp10(18), false),
+)]
+#[serial_test::serial]
+async fn evm_deposit(mint: Option<U256>, value: U256, to_self: bool) {
+    if run_on_testnet() || run_on_devnet() {
+        println!("Run on testnet or devnet");
+        return;
+    }
+    let client = client(true);
+    let sender = wallet();
+    let recipient = if to_self { sender.address() } else { wallet().address() };
+
+    let sender_before = client.get_balance(sender.address()).unwrap();
+    let recipient_before = client.get_balance(recipient).unwrap();
+
+    let rlp = Deposit::new(sender.address())
+        .to(Some(recipient))
+        .value(value)
+        .mint(mint)
+        .source_hash(H256::random())
+        .rlp();
+    client.deposit(rlp.as_ref(), &client.user_solana_wallet).await.unwrap();
+
+    let mint = mint.unwrap_or_default();
+    let transferred = if value <= mint { value } else { U256::zero() };
+    let sender_after = client.get_balance(sender.address()).unwrap();
+    let recipient_after = client.get_balance(recipient).unwrap();
+
+    if to_self {
+        assert_eq!(sender_after, sender_before + mint);
+    } else {
+        assert_eq!(sender_after, sender_before + mint - transferred);
+        assert_eq!(recipient_after, recipient_before + transferred);
+    }
+}
+
+#[rstest(
+    contract,
+    method,
+    eth_call,
+    result,
+    case::touch_storage(
+        "TouchStorage",
+        "set_value(uint256 10)",
+        "get_value",
+        "000000000000000000000000000000000000000000000000000000000000000a",
+    ),
+)]
+#[serial_test::serial]
+async fn evm_deposit_call(contract: String, method: &str, eth_call: &str, result: &str) {
+    if run_on_testnet() || run_on_devnet() {
+        println!("Run on testnet or devnet");
+        return;
+    }
+    let client = client(true);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let address = client.deploy(&contract, &wallet, None, 2).await;
+
+    // the contract is called by the deposit, nothing is minted
+    let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+    let rlp = Deposit::new(wallet.address())
+        .to(Some(address))
+        .value(U256::zero())
+        .mint(None)
+        .gas(100_000.into())
+        .data(method_id(&abi, method))
+        .source_hash(H256::random())
+        .rlp();
+    client.deposit(rlp.as_ref(), &client.user_solana_wallet).await.unwrap();
+
+    let actual = client.eth_call(&contract, &address, eth_call, &wallet);
+    assert_eq!(actual.to_vec(), hex::decode(result).unwrap());
+}
+
+#[rstest(
+    malformation,
+    system_tx,
+    case::system_tx(None, true),
+    case::truncated(Some(Malformation::Truncated), false),
+    case::trailing_bytes(Some(Malformation::TrailingBytes), false),
+    case::unknown_type(Some(Malformation::UnknownType), false),
+    case::not_a_list(Some(Malformation::NotAList), false),
+    case::empty(Some(Malformation::Empty), false),
+)]
+#[serial_test::serial]
+async fn evm_deposit_rejected(malformation: Option<Malformation>, system_tx: bool) {
+    if run_on_testnet() || run_on_devnet() {
+        println!("Run on testnet or devnet");
+        return;
+    }
+    let client = client(true);
+    let recipient = wallet().address();
+
+    let deposit = Deposit::new(recipient)
+        .value(U256::exp10(18))
+        .mint(Some(U256::exp10(18)))
+        .source_hash(H256::random())
+        .system_tx(system_tx);
+    let rlp = match malformation {
+        Some(malformation) => deposit.malformed_rlp(malformation),
+        None => deposit.rlp(),
+    };
+
+    let result = client.deposit(rlp.as_ref(), &client.user_solana_wallet).await;
+
+    assert!(result.is_err(), "deposit must be rejected: {:?}", malformation);
+    assert_eq!(client.get_balance(recipient).unwrap(), U256::zero());
+}
+
+#[rstest]
+#[serial_test::serial]
+async fn evm_deposit_duplicate_source_hash() {
+    if run_on_testnet() || run_on_devnet() {
+        println!("Run on testnet or devnet");
+        return;
+    }
+    let client = client(true);
+    let recipient = wallet().address();
+    let source_hash = H256::random();
+
+    let first = Deposit::new(recipient)
+        .value(U256::exp10(18))
+        .mint(Some(U256::exp10(18)))
+        .source_hash(source_hash);
+    client.deposit(first.rlp().as_ref(), &client.user_solana_wallet).await.unwrap();
+    let balance = client.get_balance(recipient).unwrap();
+    assert_eq!(balance, U256::exp10(18));
+
+    // the same deposit is replayed
+    let result = client.deposit(first.rlp().as_ref(), &client.user_solana_wallet).await;
+    assert!(result.is_err());
+
+    // another deposit that reuses the source hash
+    let second = first.clone().mint(Some(U256::exp10(18) * 2));
+    let result = client.deposit(second.rlp().as_ref(), &client.user_solana_wallet).await;
+    assert!(result.is_err());
+
+    assert_eq!(client.get_balance(recipient).unwrap(), balance);
+}
+
+/// Result of the withdrawal expected by the test case
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Outcome {
+    /// the value moves to the predeployed contract, the lamports to the solana account
+    Success,
+    /// the transaction is not executed: the nonce is not incremented, no fee is charged
+    Rejected,
+    /// the transaction is executed with status 0: the nonce is incremented, only the fee is charged
+    Reverted,
+}
+
+/// Solana account receiving the withdrawal
+#[derive(Clone, Copy, Debug)]
+enum Target {
+    /// existing account, see [test_account]
+    Funded,
+    /// account that does not exist before the withdrawal
+    New,
+}
+
+/// Rent-exempt minimum of the account without data, lamports
+const RENT_EXEMPT_MINIMUM: u64 = 890_880;
+
+///  The sender has 10 SOL. The withdrawn value is converted to lamports with truncation: wei / 10^9.
+///  lamports is the exact increase of the solana balance.
+///  via_contract: the withdrawal is made by Caller.callAnySOLWithdrawal
+#[rstest(
+    amount,
+    target,
+    via_contract,
+    expected,
+    lamports,
+    case::zero(U256::zero(), Target::Funded, false, Outcome::Success, 0),
+    case::sub_lamport(U256::from(999_999_999u64), Target::Funded, false, Outcome::Success, 0),
+    case::truncated(U256::exp10(18) + U256::from(999_999_999u64), Target::Funded, false, Outcome::Success, 1_000_000_000),
+    case::above_balance(U256::exp10(19) * 2, Target::Funded, false, Outcome::Rejected, 0),
+    case::new_account(U256::exp10(18), Target::New, false, Outcome::Success, 1_000_000_000),
+    case::rent_exempt_minimum(to_wei(RENT_EXEMPT_MINIMUM), Target::New, false, Outcome::Success, RENT_EXEMPT_MINIMUM),
+    case::below_rent_exempt_minimum(to_wei(RENT_EXEMPT_MINIMUM - 1), Target::New, false, Outcome::Reverted, 0),
+    case::contract_zero(U256::zero(), Target::Funded, true, Outcome::Success, 0),
+    case::contract_1_gwei(U256::from(1_000_000_000u64), Target::Funded, true, Outcome::Success, 1),
+    case::contract_truncated(U256::exp10(17) * 25 + U256::one(), Target::Funded, true, Outcome::Success, 2_500_000_000),
+    case::contract_above_balance(U256::exp10(19) * 2, Target::Funded, true, Outcome::Rejected, 0),
+    case::contract_below_rent_exempt_minimum(to_wei(RENT_EXEMPT_MINIMUM - 1), Target::New, true, Outcome::Reverted, 0),
+)]
+#[serial_test::serial]
+async fn withdraw_edge_cases(
+    amount: U256,
+    target: Target,
+    via_contract: bool,
+    expected: Outcome,
+    lamports: u64,
+) {
+    let zero_gas = false;
+    let client = client(zero_gas);
+    let sender = wallet();
+    let predeployed = Address::from_str(WITHDRAWAL_ADDRESS).unwrap();
+    let withdraw_key = match target {
+        Target::Funded => test_account(),
+        Target::New => Keypair::new().pubkey(),
+    };
+    client.airdrop(sender.address(), U256::exp10(19)).await;
+
+    let contract = "Caller".to_string();
+    let (to, call_data) = if via_contract {
+        let address = client.deploy(&contract, &sender, None, 2).await;
+        let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+        let method = format!("callAnySOLWithdrawal(bytes32 0x{})", hex::encode(withdraw_key.to_bytes()));
+        (address, method_id(&abi, &method))
+    } else {
+        let selector = ethers::utils::id("withdrawal(bytes32)")[..4].to_vec();
+        let args = ethers::abi::encode(&[ethers::abi::Token::FixedBytes(withdraw_key.to_bytes().to_vec())]);
+        (predeployed, [selector, args].concat())
+    };
+
+    // balances before
+    let fee_balance_before = client.sum_fee_balances(zero_gas).await;
+    let sender_balance_before = client.get_balance(sender.address()).unwrap();
+    let contract_balance_before = client.get_balance(to).unwrap();
+    let balance_predeployed_before = client.get_balance(predeployed).unwrap();
+    let solana_balance_before = client.solana_rpc.get_balance(&withdraw_key).await.unwrap();
+    let nonce_before = client.transaction_count(sender.address()).unwrap();
+
+    // withdrawal, the gas is not estimated because the transaction may fail
+    let tx = do_tx_with_gas(&client, Some(to), call_data, &sender, amount, 2, 300_000.into());
+    if expected == Outcome::Reverted {
+        assert!(!emulate_tx(&client, &tx, &sender).await.status, "the withdrawal must revert");
+    }
+    let result = client.try_send_tx(&tx, &sender).await;
+    println!("withdrawal of {} wei: {:?}", amount, result);
+
+    // balances after
+    let fee_diff = client.sum_fee_balances(zero_gas).await - fee_balance_before;
+    let sender_balance_after = client.get_balance(sender.address()).unwrap();
+    let contract_balance_after = client.get_balance(to).unwrap();
+    let balance_predeployed_after = client.get_balance(predeployed).unwrap();
+    let solana_balance_after = client.solana_rpc.get_balance(&withdraw_key).await.unwrap();
+    let nonce_after = client.transaction_count(sender.address()).unwrap();
+
+    match expected {
+        Outcome::Success => {
+            assert!(result.is_ok());
+            assert_eq!(nonce_after, nonce_before + 1);
+            assert_eq!(sender_balance_after, sender_balance_before - amount - fee_diff);
+            assert_eq!(balance_predeployed_after, balance_predeployed_before + amount);
+            assert_eq!(solana_balance_after, solana_balance_before + lamports);
+            assert_eq!(lamports, to_lamports(amount));
+        },
+        Outcome::Rejected => {
+            assert!(result.is_err());
+            assert_eq!(nonce_after, nonce_before);
+            assert_eq!(fee_diff, U256::zero());
+            assert_eq!(sender_balance_after, sender_balance_before);
+            assert_eq!(balance_predeployed_after, balance_predeployed_before);
+            assert_eq!(solana_balance_after, solana_balance_before);
+            assert_eq!(lamports, 0);
+        },
+        Outcome::Reverted => {
+            assert!(result.is_ok());
+            assert_eq!(nonce_after, nonce_before + 1);
+            assert!(fee_diff > U256::zero());
+            assert_eq!(sender_balance_after, sender_balance_before - fee_diff);
+            assert_eq!(balance_predeployed_after, balance_predeployed_before);
+            assert_eq!(solana_balance_after, solana_balance_before);
+            assert_eq!(lamports, 0);
+        },
+    }
+
+    // the contract forwards the whole msg.value
+    if via_contract {
+        assert_eq!(contract_balance_after, contract_balance_before);
+    }
+}
+
+/// Maximum compute units of the solana transaction
+const MAX_COMPUTE_UNITS: u64 = 1_400_000;
+
+///  mode: execution path expected from rome-evm, None if it is not checked
+#[rstest(
+    contract,
+    method,
+    mode,
+    case::atomic_rw("AtomicIterative", "atomic_rw", Some(ExecutionMode::Atomic)),
+    case::atomic_ro("AtomicIterative", "atomic_ro", Some(ExecutionMode::Atomic)),
+    case::iterative_rw("AtomicIterative", "iterative_rw", Some(ExecutionMode::Iterative)),
+    case::iterative_ro("AtomicIterative", "iterative_ro", Some(ExecutionMode::Iterative)),
+    case::cu_update("CU", "update", None),
+    case::cu_update_single("CU", "update_single", None),
+    case::cu_push("CU", "push", None),
+)]
+#[serial_test::serial]
+async fn evm_resource_usage(contract: String, method: &str, mode: Option<ExecutionMode>) {
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let address = client.deploy(&contract, &wallet, None, 2).await;
+
+    let report = client.method_call_with_report(&contract, &address, method, &wallet, 0.into(), 2).await;
+    report.log(&format!("{}.{}", contract, method));
+
+    if let Some(mode) = mode {
+        report.assert_mode(mode);
+    }
+    if report.mode() == ExecutionMode::Atomic {
+        assert_eq!(report.count(), 1);
+    } else {
+        assert!(report.count() > 1);
+    }
+    if report.mode().is_iterative() {
+        assert!(report.iterations() > 0);
+    }
+
+    let mut signatures = report.signatures();
+    signatures.sort();
+    signatures.dedup();
+    assert_eq!(signatures.len(), report.count());
+
+    for tx in &report.solana_txs {
+        assert!(tx.compute_units > 0);
+        assert!(tx.compute_units <= MAX_COMPUTE_UNITS);
+        assert!(tx.fee > 0);
+    }
+    assert_eq!(report.lamports(), report.solana_txs.iter().map(|tx| tx.fee).sum::<u64>());
+}
+
+///  The calldata larger than a solana transaction is transmitted to the holder account
+#[rstest(
+    len,
+    holder,
+    case::small(32, false),
+    case::large(4_000, true),
+    case::huge(20_000, true),
+)]
+#[serial_test::serial]
+async fn evm_execution_mode_holder(len: usize, holder: bool) {
+    let contract = "TouchStorage".to_string();
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let address = client.deploy(&contract, &wallet, None, 2).await;
+
+    let method = format!("set_text(string {})", "a".repeat(len));
+    let report = client.method_call_with_report(&contract, &address, &method, &wallet, 0.into(), 2).await;
+    report.log(&format!("{}.set_text({} bytes)", contract, len));
+
+    assert_eq!(report.mode().is_holder(), holder);
+}
+
+/// Generated contracts up to the EIP-170 limit are deployed and called with the large payload.
+/// The init code that does not fit into a solana transaction is deployed through the holder account.
+/// eth_getCode is compared with geth in state_comparison_2
+#[rstest(
+    code_size,
+    ctor_size,
+    call_size,
+    holder,
+    case::small(100, 0, 32, false),
+    case::near_limit(MAX_CODE_SIZE - 1, 0, 4_000, true),
+    case::limit(MAX_CODE_SIZE, 0, 20_000, true),
+    case::large_ctor(1_000, 20_000, 32, true),
+)]
+#[serial_test::serial]
+async fn evm_large_contract(code_size: usize, ctor_size: usize, call_size: usize, holder: bool) {
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+
+    let runtime = runtime_code(code_size);
+    let bytecode = init_code(&runtime, &payload(ctor_size));
+    let address = calc_address(&client, &wallet.address());
+    let tx = do_tx(&client, None, bytecode.clone(), &wallet, 0.into(), 2);
+    let report = client.send_tx_with_report(&tx, &wallet).await;
+    report.log(&format!("deploy({} bytes)", bytecode.len()));
+    assert_eq!(report.mode().is_holder(), holder);
+
+    let tx = do_tx(&client, Some(address), payload(call_size), &wallet, 0.into(), 2);
+    let result = client.call(&tx.clone().into()).unwrap();
+    assert_eq!(U256::from_big_endian(&result), U256::from(RETURN_VALUE));
+
+    let report = client.send_tx_with_report(&tx, &wallet).await;
+    report.log(&format!("call({} bytes)", call_size));
+}
+
+/// CREATE2 addresses of the factory children match calc_create2_address and the factory's own calculation
+#[rstest(
+    salts,
+    case::single(vec![1]),
+    case::different_salts(vec![1, 2, 0xff]),
+)]
+#[serial_test::serial]
+async fn evm_create2(salts: Vec<u8>) {
+    let factory = "Create2Factory".to_string();
+    let child = "Create2Child".to_string();
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+
+    let factory_addr = client.deploy(&factory, &wallet, None, 2).await;
+    let init_code = std::fs::read(format!("{}{}.binary", CONTRACTS, child)).unwrap();
+
+    let mut children = vec![];
+    for salt in salts {
+        let salt = [salt; 32];
+        let expected = calc_create2_address(&factory_addr, salt, &init_code);
+
+        let calc = format!("calc(bytes32 0x{})", hex::encode(salt));
+        let calc = client.eth_call(&factory, &factory_addr, &calc, &wallet);
+        assert_eq!(Address::from_slice(&calc[12..]), expected);
+
+        let deploy = format!("deploy(bytes32 0x{})", hex::encode(salt));
+        client.method_call(&factory, &factory_addr, &deploy, &wallet, 0.into(), 2).await;
+        let last = client.eth_call(&factory, &factory_addr, "last", &wallet);
+        assert_eq!(Address::from_slice(&last[12..]), expected);
+
+        let value = client.eth_call(&child, &expected, "get", &wallet);
+        assert_eq!(U256::from_big_endian(&value), U256::one());
+        children.push(expected);
+    }
+
+    // different salts give different addresses
+    let unique: std::collections::BTreeSet<_> = children.iter().collect();
+    assert_eq!(unique.len(), children.len());
+}
+
+/// How the first child is removed before the CREATE2 with the same salt
+#[derive(Clone, Copy, Debug)]
+enum Removal {
+    /// the child is kept
+    None,
+    /// selfdestruct in a separate transaction, EIP-6780 keeps the code
+    SeparateTx,
+    /// selfdestruct in the creating transaction, EIP-6780 removes the account
+    SameTx,
+}
+
+#[rstest(
+    removal,
+    redeployed,
+    case::collision(Removal::None, false),
+    case::destruct_separate_tx(Removal::SeparateTx, false),
+    case::destruct_same_tx(Removal::SameTx, true),
+)]
+#[serial_test::serial]
+async fn evm_create2_redeploy(removal: Removal, redeployed: bool) {
+    let factory = "Create2Factory".to_string();
+    let child = "Create2Child".to_string();
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+
+    let factory_addr = client.deploy(&factory, &wallet, None, 2).await;
+    let init_code = std::fs::read(format!("{}{}.binary", CONTRACTS, child)).unwrap();
+    let salt = [7u8; 32];
+    let expected = calc_create2_address(&factory_addr, salt, &init_code);
+    let deploy = format!("deploy(bytes32 0x{})", hex::encode(salt));
+
+    match removal {
+        Removal::None => {
+            client.method_call(&factory, &factory_addr, &deploy, &wallet, 0.into(), 2).await;
+        },
+        Removal::SeparateTx => {
+            client.method_call(&factory, &factory_addr, &deploy, &wallet, 0.into(), 2).await;
+            client.method_call(&child, &expected, "destruct", &wallet, 0.into(), 2).await;
+        },
+        Removal::SameTx => {
+            let method = format!("deploy_and_destruct(bytes32 0x{})", hex::encode(salt));
+            client.method_call(&factory, &factory_addr, &method, &wallet, 0.into(), 2).await;
+        },
+    }
+
+    // the code of the first child
+    let value = client.eth_call(&child, &expected, "get", &wallet);
+    match removal {
+        Removal::SameTx => assert!(value.is_empty(), "child destroyed in the creating tx must be removed"),
+        _ => assert_eq!(U256::from_big_endian(&value), U256::one()),
+    }
+
+    // CREATE2 with the same salt
+    let abi = abi(&format!("{}{}.abi", CONTRACTS, factory));
+    let tx = do_tx_with_gas(&client, Some(factory_addr), method_id(&abi, &deploy), &wallet, 0.into(), 2, 1_000_000.into());
+    let call = client.call(&tx.clone().into());
+    println!("redeploy: {:?}", call);
+    assert_eq!(call.is_ok(), redeployed);
+
+    if redeployed {
+        client.send_tx(&tx, &wallet).await;
+        let value = client.eth_call(&child, &expected, "get", &wallet);
+        assert_eq!(U256::from_big_endian(&value), U256::one());
+    }
+}
+
+/// Cancun EIP-6780:
+///  - selfdestruct in the creating transaction removes the code, storage and nonce of the account,
+///  - selfdestruct in a later transaction only transfers the balance, the account is kept.
+/// The balance is sent to DestructCaller in both cases
+#[rstest]
+#[serial_test::serial]
+async fn evm_selfdestruct() {
+    let contract = "DestructCaller".to_string();
+    let child = "Destruct".to_string();
+    let value = U256::exp10(15);
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let caller = client.deploy(&contract, &wallet, None, 2).await;
+    let address = |method: &str| {
+        let result = client.eth_call(&contract, &caller, method, &wallet);
+        Address::from_slice(&result[12..])
+    };
+
+    // selfdestruct in the creating transaction
+    client.method_call(&contract, &caller, "deploy_and_destruct", &wallet, value, 2).await;
+    let addr2 = address("addr2");
+    assert!(client.eth_call(&child, &addr2, "get", &wallet).is_empty(), "code must be removed");
+    assert_eq!(client.get_balance(addr2).unwrap(), U256::zero());
+    assert_eq!(client.eth_get_storage_at(addr2, U256::zero()).unwrap(), U256::zero());
+    assert_eq!(client.transaction_count(addr2).unwrap(), U256::zero());
+    assert_eq!(client.get_balance(caller).unwrap(), value);
+
+    // selfdestruct in a later transaction
+    client.method_call(&contract, &caller, "deploy", &wallet, value, 2).await;
+    let addr1 = address("addr1");
+    assert_eq!(client.get_balance(addr1).unwrap(), value);
+
+    client.method_call(&contract, &caller, "destruct_first", &wallet, 0.into(), 2).await;
+    let result = client.eth_call(&child, &addr1, "get", &wallet);
+    assert_eq!(U256::from_big_endian(&result), U256::one(), "code must be kept");
+    assert_eq!(client.get_balance(addr1).unwrap(), U256::zero());
+    assert_eq!(client.eth_get_storage_at(addr1, U256::zero()).unwrap(), U256::one());
+    assert_eq!(client.transaction_count(addr1).unwrap(), U256::one());
+    assert_eq!(client.get_balance(caller).unwrap(), value * 2);
+
+    client.method_call(&contract, &caller, "check", &wallet, 0.into(), 2).await;
+}
+
+/// EIP-1153: the transient value is visible in the internal calls, the writes of the reverted
+/// calls are discarded and the value is cleared at the end of the transaction.
+///  logs: (step, value) of the Loaded events
+#[rstest(
+    method,
+    logs,
+    result,
+    case::set("set(uint256 5)", vec![(0, 5)], 5),
+    case::nested("nested(uint256 5)", vec![(1, 5), (2, 6), (3, 6)], 6),
+)]
+#[serial_test::serial]
+async fn evm_transient_storage(method: &str, logs: Vec<(u64, u64)>, result: u64) {
+    let contract = "TransientProbe".to_string();
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let address = client.deploy(&contract, &wallet, None, 2).await;
+    let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+    let loaded = |emulation: &shared::emulation::EmulationReport| -> Vec<(u64, u64)> {
+        emulation
+            .logs
+            .iter()
+            .map(|log| {
+                let step = U256::from_big_endian(&log.data[..32]).as_u64();
+                let value = U256::from_big_endian(&log.data[32..64]).as_u64();
+                (step, value)
+            })
+            .collect()
+    };
+
+    // values observed during the transaction
+    let tx = do_tx(&client, Some(address), method_id(&abi, method), &wallet, 0.into(), 2);
+    let emulation = emulate_tx(&client, &tx, &wallet).await;
+    assert_eq!(loaded(&emulation), logs);
+    assert_eq!(U256::from_big_endian(&emulation.return_data), result.into());
+    assert!(emulation.slots(&address).is_empty(), "transient storage must not touch the storage");
+
+    let call = client.call(&tx.clone().into()).unwrap();
+    assert_eq!(U256::from_big_endian(&call), result.into());
+    client.send_tx(&tx, &wallet).await;
+
+    // the value is cleared at the end of the transaction
+    let tx = do_tx(&client, Some(address), method_id(&abi, "load"), &wallet, 0.into(), 2);
+    let emulation = emulate_tx(&client, &tx, &wallet).await;
+    assert_eq!(loaded(&emulation), vec![(0, 0)]);
+    client.send_tx(&tx, &wallet).await;
+
+    let value = client.eth_call(&contract, &address, "get", &wallet);
+    assert_eq!(U256::from_big_endian(&value), U256::zero());
+}
+
+/// Precompiles are called directly and through PrecompileProbe with the vectors of shared::precompile.
+/// The gas measured by the probe matches the gas schedule of the vector
+#[rstest(
+    name,
+    case::ecrecover("ecrecover"),
+    case::ecrecover_invalid_v("ecrecover_invalid_v"),
+    case::sha256_empty("sha256_empty"),
+    case::sha256("sha256"),
+    case::ripemd160_empty("ripemd160_empty"),
+    case::ripemd160("ripemd160"),
+    case::identity("identity"),
+    case::modexp("modexp"),
+    case::modexp_small("modexp_small"),
+    case::bn256_add("bn256_add"),
+    case::bn256_add_not_on_curve("bn256_add_not_on_curve"),
+    case::bn256_mul("bn256_mul"),
+    case::bn256_pairing_empty("bn256_pairing_empty"),
+    case::blake2f("blake2f"),
+    case::point_evaluation("point_evaluation"),
+    case::point_evaluation_invalid_length("point_evaluation_invalid_length"),
+)]
+#[serial_test::serial]
+async fn evm_precompile(name: &str) {
+    let vector = vector(name);
+    let contract = "PrecompileProbe".to_string();
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+
+    // direct call
+    let gas = U256::from(vector.tx_gas() + 100_000);
+    let tx = do_tx_with_gas(&client, Some(vector.address()), vector.input.clone(), &wallet, 0.into(), 0, gas);
+    let result = client.call(&tx.clone().into());
+    println!("{}: {:?}", name, result);
+    match &vector.output {
+        Some(output) => {
+            assert_eq!(&result.unwrap().to_vec(), output);
+
+            let request = ethers::types::TransactionRequest::new()
+                .from(wallet.address())
+                .to(vector.address())
+                .data(vector.input.clone());
+            let estimate = client.estimate_gas(&request).unwrap();
+            assert!(estimate >= vector.tx_gas().into(), "estimate {} < {}", estimate, vector.tx_gas());
+        },
+        None => assert!(result.is_err()),
+    }
+
+    // call through the contract
+    let address = client.deploy(&contract, &wallet, None, 2).await;
+    let (_, _, identity_empty) = probe(&client, address, &wallet, Address::from_low_u64_be(4), vec![]);
+    let (success, output, used) = probe(&client, address, &wallet, vector.address(), vector.input.clone());
+    assert_eq!(success, vector.output.is_some());
+    assert_eq!(output, vector.output.clone().unwrap_or_default());
+    if vector.output.is_some() {
+        assert_eq!(probe_gas(used, identity_empty), vector.gas, "gas schedule of {}", name);
+    }
+
+    let tx = probe_tx(&client, address, &wallet, vector.address(), vector.input.clone());
+    let report = client.send_tx_with_report(&tx, &wallet).await;
+    report.log(&format!("precompile {}", name));
+}
+
+/// Vectors of the go-ethereum test data: the output of the direct call
+/// and the gas measured by PrecompileProbe
+#[rstest(
+    file,
+    precompile,
+    case::ecrecover("ecRecover.json", 1),
+    case::modexp("modexp_eip2565.json", 5),
+    case::bn256_add("bn256Add.json", 6),
+    case::bn256_mul("bn256ScalarMul.json", 7),
+    case::bn256_pairing("bn256Pairing.json", 8),
+    case::blake2f("blake2F.json", 9),
+    case::point_evaluation("pointEvaluation.json", 10),
+)]
+#[serial_test::serial]
+async fn evm_precompile_vectors(file: &str, precompile: u8) {
+    let contract = "PrecompileProbe".to_string();
+    let client = client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+    let address = client.deploy(&contract, &wallet, None, 2).await;
+    let (_, _, identity_empty) = probe(&client, address, &wallet, Address::from_low_u64_be(4), vec![]);
+
+    for vector in load_vectors(file, precompile) {
+        println!("{}", vector.name);
+        let gas = U256::from(vector.tx_gas() + 100_000);
+        let tx = do_tx_with_gas(&client, Some(vector.address()), vector.input.clone(), &wallet, 0.into(), 0, gas);
+        let result = client.call(&tx.into()).unwrap();
+        assert_eq!(Some(result.to_vec()), vector.output, "output of {}", vector.name);
+
+        let (success, output, used) = probe(&client, address, &wallet, vector.address(), vector.input.clone());
+        assert!(success, "{} failed through the probe", vector.name);
+        assert_eq!(Some(output), vector.output, "probe output of {}", vector.name);
+        assert_eq!(probe_gas(used, identity_empty), vector.gas, "gas schedule of {}", vector.name);
+    }
+}
+
+fn probe_tx(
+    client: &Client,
+    address: Address,
+    wallet: &ethers_signers::Wallet<ethers_core::k256::ecdsa::SigningKey>,
+    precompile: Address,
+    input: Vec<u8>,
+) -> ethers_core::types::transaction::eip2718::TypedTransaction {
+    let selector = ethers::utils::id("probe(address,bytes)")[..4].to_vec();
+    let args = ethers::abi::encode(&[ethers::abi::Token::Address(precompile), ethers::abi::Token::Bytes(input)]);
+    do_tx(client, Some(address), [selector, args].concat(), wallet, 0.into(), 2)
+}
+
+/// eth_call of PrecompileProbe.probe: success, output and the gas used by the staticcall
+fn probe(
+    client: &Client,
+    address: Address,
+    wallet: &ethers_signers::Wallet<ethers_core::k256::ecdsa::SigningKey>,
+    precompile: Address,
+    input: Vec<u8>,
+) -> (bool, Vec<u8>, U256) {
+    let tx = probe_tx(client, address, wallet, precompile, input);
+    let result = client.call(&tx.into()).unwrap();
+    let types = [ethers::abi::ParamType::Bool, ethers::abi::ParamType::Bytes, ethers::abi::ParamType::Uint(256)];
+    let decoded = ethers::abi::decode(&types, &result).unwrap();
+    (
+        decoded[0].clone().into_bool().unwrap(),
+        decoded[1].clone().into_bytes().unwrap(),
+        decoded[2].clone().into_uint().unwrap(),
+    )
+}
+
+/// The invalid transactions are rejected by send_transaction with the error of the sdk,
+/// the nonce and the balance of the sender do not change. The rome-evm program has no base fee,
+/// the fee cap case is checked by state_comparison_2 only
+#[rstest(
+    invalid,
+    case::wrong_chain_id(Invalid::WrongChainId),
+    case::bad_signature(Invalid::BadSignature),
+    case::nonce_too_low(Invalid::NonceTooLow),
+    case::nonce_too_high(Invalid::NonceTooHigh),
+    case::gas_below_intrinsic(Invalid::GasBelowIntrinsic),
+    case::value_above_balance(Invalid::ValueAboveBalance),
+    case::oversized_calldata(Invalid::OversizedCalldata),
+    case::unprotected_legacy(Invalid::UnprotectedLegacy),
+)]
+#[serial_test::serial]
+async fn evm_invalid_tx(invalid: Invalid) {
+    let client = mode_client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(18)).await;
+    // the nonce of the sender is not zero
+    client.transfer(&wallet, &shared::wallet().address(), 1.into()).await;
+
+    let nonce = client.transaction_count(wallet.address()).unwrap();
+    let balance = client.get_balance(wallet.address()).unwrap();
+
+    let rlp = invalid_rlp(&client, &wallet, invalid);
+    let error = match client.send_transaction(rlp.into()).await {
+        Ok(_) => panic!("{:?} transaction must be rejected", invalid),
+        Err(e) => format!("{:?}", e),
+    };
+    println!("[ Invalid ] - {:?}: {}", invalid, error);
+
+    let expected = invalid.error(Target::Sdk).unwrap();
+    assert!(error.contains(expected), "{:?}: expected error '{}', got {}", invalid, expected, error);
+    assert_eq!(client.transaction_count(wallet.address()).unwrap(), nonce);
+    assert_eq!(client.get_balance(wallet.address()).unwrap(), balance);
+}
+
+/// Random transactions are sent by send_transaction within the budget, see FuzzBudget.
+/// The nonce increments only for the accepted transaction, the balances of the sender,
+/// the receiver and the fee recipients are conserved
+#[rstest]
+#[serial_test::serial]
+async fn evm_fuzz_raw_tx() {
+    let client = mode_client(false);
+    let wallet = wallet();
+    client.airdrop(wallet.address(), U256::exp10(19)).await;
+
+    let budget = FuzzBudget::from_env();
+    let mut runner = budget.runner();
+    let gas_price = client.gas_price().unwrap();
+    let fee_recipients = Client::get_fee_addresses(false);
+    let start = Instant::now();
+    let mut case = 0;
+
+    while !budget.exhausted(case, start) {
+        let fuzz = generate(&fuzz_tx(), &mut runner);
+        let receiver = shared::wallet().address();
+        let accounts: Vec<Address> = [wallet.address(), receiver].into_iter().chain(fee_recipients.clone()).collect();
+        let total = || accounts.iter().fold(U256::zero(), |sum, a| sum + client.get_balance(*a).unwrap());
+
+        let nonce = client.transaction_count(wallet.address()).unwrap();
+        let balance = client.get_balance(wallet.address()).unwrap();
+        let before = total();
+
+        let tx = fuzz.build(&wallet, receiver, nonce, client.chain_id(), gas_price, balance);
+        let result = client.send_transaction(fuzz.rlp(&tx, &wallet).into()).await;
+        let accepted = result.is_ok();
+        println!("[ Fuzz ] - case {}: {:?}, accepted {}", case, fuzz, accepted);
+
+        let expected_nonce = if accepted { nonce + 1 } else { nonce };
+        assert_eq!(client.transaction_count(wallet.address()).unwrap(), expected_nonce, "nonce: {:?}", fuzz);
+        assert_eq!(total(), before, "balances are not conserved: {:?}", fuzz);
+        let received = if accepted { *tx.value().unwrap() } else { U256::zero() };
+        assert_eq!(client.get_balance(receiver).unwrap(), received, "receiver balance: {:?}", fuzz);
+
+        case += 1;
+    }
+    println!("[ Fuzz ] - {} cases in {:.2}s", case, start.elapsed().as_secs_f64());
+}
@@ -0,0 +1,139 @@
+mod shared;
+
+use ethers::providers::Middleware;
+use ethers_core::types::{U256, U64};
+use ethers_signers::Signer;
+use rstest::*;
+use serial_test::serial;
+use shared::{
+    differential::{minimize, program, run, save_regression},
+    fuzz::{fuzz_tx, generate, FuzzBudget, FuzzCase, FuzzTx},
+    utils::{airdrop_to_address, get_chain_id, get_random_wallet, return_current_provider, wait_for_inclusion},
+};
+use std::time::Instant;
+
+/// Executions of the minimization of the differing program
+const MINIMIZE_RUNS: usize = 32;
+
+/// Decision of the provider on the fuzz transaction
+#[derive(Clone, Debug, PartialEq)]
+enum Decision {
+    Rejected,
+    /// the status of the receipt
+    Included(Option<U64>),
+    Dropped,
+}
+
+/// Send the fuzz transaction from the sender to a new account and check the invariants:
+/// the nonce increments only for the included transaction,
+/// the sender pays the value and the gas fee, the receiver gets the value
+async fn send(
+    fuzz: &FuzzTx,
+    provider_name: &str,
+    sender: &ethers_signers::LocalWallet,
+) -> Result<Decision, Box<dyn std::error::Error>> {
+    let provider = return_current_provider(provider_name);
+    let receiver = get_random_wallet().address();
+    let nonce = provider.get_transaction_count(sender.address(), None).await?;
+    let balance = provider.get_balance(sender.address(), None).await?;
+    let gas_price = provider.get_gas_price().await?;
+
+    let tx = fuzz.build(sender, receiver, nonce, get_chain_id(), gas_price, balance);
+    let rlp = fuzz.rlp(&tx, sender);
+
+    let decision = match provider.send_raw_transaction(rlp.into()).await {
+        Err(e) => {
+            println!("[ Fuzz ] - {}: rejected: {}", provider_name, e);
+            Decision::Rejected
+        },
+        Ok(pending) => match wait_for_inclusion(pending).await {
+            Ok(receipt) => {
+                let value = if receipt.status == Some(1.into()) { *tx.value().unwrap() } else { U256::zero() };
+                let fee = receipt.gas_used.unwrap() * receipt.effective_gas_price.unwrap_or(gas_price);
+                assert_eq!(provider.get_balance(sender.address(), None).await?, balance - value - fee, "sender balance");
+                assert_eq!(provider.get_balance(receiver, None).await?, value, "receiver balance");
+                Decision::Included(receipt.status)
+            },
+            Err(_) => Decision::Dropped,
+        },
+    };
+    let expected_nonce = if matches!(decision, Decision::Included(_)) { nonce + 1 } else { nonce };
+    assert_eq!(provider.get_transaction_count(sender.address(), None).await?, expected_nonce, "nonce");
+    Ok(decision)
+}
+
+/// Random transactions are sent to geth and proxy from the senders with the same balance and nonce,
+/// the decisions must match. The budget is set by FUZZ_CASES, FUZZ_DURATION and FUZZ_SEED
+#[rstest]
+#[serial]
+async fn raw_tx_fuzz() -> Result<(), Box<dyn std::error::Error>> {
+    let total_start = Instant::now();
+
+    let geth_sender = get_random_wallet();
+    let proxy_sender = get_random_wallet();
+    airdrop_to_address(geth_sender.address(), U256::exp10(18), "geth").await?;
+    airdrop_to_address(proxy_sender.address(), U256::exp10(18), "proxy").await?;
+
+    let budget = FuzzBudget::from_env();
+    let mut runner = budget.runner();
+    let mut case = 0;
+
+    while !budget.exhausted(case, total_start) {
+        let fuzz = generate(&fuzz_tx(), &mut runner);
+        let fuzz_case = FuzzCase::new(&budget, case, format!("{:?}", fuzz));
+
+        let geth = send(&fuzz, "geth", &geth_sender).await?;
+        let proxy = send(&fuzz, "proxy", &proxy_sender).await?;
+        assert_eq!(proxy, geth, "decision of proxy differs from geth");
+        fuzz_case.pass();
+
+        case += 1;
+    }
+
+    println!(
+        "[ {:.2}s ] - Total duration, {} cases\n",
+        total_start.elapsed().as_secs_f64(),
+        case
+    );
+    Ok(())
+}
+
+/// Random bytecode is deployed and called through the proxy, the results of proxy and geth are compared.
+/// The differing program is minimized and saved as the regression case, see save_regression
+#[rstest]
+#[serial]
+async fn bytecode_fuzz() -> Result<(), Box<dyn std::error::Error>> {
+    let total_start = Instant::now();
+
+    let sender = get_random_wallet();
+    airdrop_to_address(sender.address(), U256::exp10(18), "proxy").await?;
+
+    let budget = FuzzBudget::from_env();
+    let mut runner = budget.runner();
+    let mut failures = vec![];
+    let mut case = 0;
+
+    while !budget.exhausted(case, total_start) {
+        let program = generate(&program(), &mut runner);
+        let fuzz_case = FuzzCase::new(&budget, case, hex::encode(program.code()));
+
+        if !run(&program, &sender).await.is_empty() {
+            let minimized = minimize(&program, &sender, MINIMIZE_RUNS).await;
+            let name = format!("seed_{}_case_{}", budget.seed, case);
+            save_regression(&minimized, &name);
+            failures.push(name);
+        } else {
+            fuzz_case.pass();
+        }
+
+        case += 1;
+    }
+
+    println!(
+        "[ {:.2}s ] - Total duration, {} cases\n",
+        total_start.elapsed().as_secs_f64(),
+        case
+    );
+    assert!(failures.is_empty(), "proxy differs from geth: {:?}", failures);
+    Ok(())
+}
@@ -0,0 +1,109 @@
+mod shared;
+
+use ethers_core::types::U256;
+use rstest::*;
+use serial_test::serial;
+use shared::{mempool::Mempool, utils::DROPPED};
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Scenario {
+    /// the transaction with the nonce gap waits for the missing nonce
+    NonceGap,
+    /// the pending transaction is replaced by one with a higher fee, a too small fee bump is rejected
+    Replacement,
+    /// the fee cap below the base fee of the latest block
+    Underpriced,
+    /// the same transaction is sent twice
+    Duplicate,
+    /// the replaced transaction is dropped from the mempool, waiting for it is an error,
+    /// the wait of airdrop_to_address succeeds
+    Dropped,
+}
+
+async fn run(scenario: Scenario, provider_name: &str) -> Vec<String> {
+    let mut pool = Mempool::new(provider_name).await;
+    let price = pool.gas_price;
+
+    match scenario {
+        Scenario::NonceGap => {
+            pool.send("second", pool.transfer(1, price)).await;
+            pool.visible("second").await;
+            assert_eq!(pool.pending_nonce().await, 0, "nonce gap must not advance the pending nonce");
+
+            pool.send("first", pool.transfer(0, price)).await;
+            pool.settle(2).await;
+            let order = pool.included(&["first", "second"]).await;
+            assert_eq!(order, vec!["first", "second"]);
+        },
+        Scenario::Replacement => {
+            // the gap keeps the transactions pending
+            pool.send("original", pool.transfer(1, price)).await;
+            pool.send("small_bump", pool.transfer(1, price + 1)).await;
+            pool.send("replacement", pool.transfer(1, price * 2)).await;
+            pool.visible("replacement").await;
+
+            pool.send("first", pool.transfer(0, price)).await;
+            pool.settle(2).await;
+            let order = pool.included(&["first", "original", "small_bump", "replacement"]).await;
+            assert_eq!(order, vec!["first", "replacement"]);
+        },
+        Scenario::Underpriced => {
+            // the decisions of the providers are logged and compared, the transaction is not executable
+            let base_fee = pool.base_fee().await;
+            pool.send("underpriced", pool.transfer_1559(0, base_fee.saturating_sub(U256::one()))).await;
+            pool.visible("underpriced").await;
+            pool.pending_nonce().await;
+
+            // the transaction at the gas price replaces the underpriced one
+            pool.send("min_price", pool.transfer(0, price)).await;
+            pool.settle(1).await;
+            pool.included(&["underpriced", "min_price"]).await;
+        },
+        Scenario::Duplicate => {
+            let raw = pool.transfer(0, price);
+            assert!(pool.send("original", raw.clone()).await);
+            pool.send("duplicate", raw.clone()).await;
+            pool.settle(1).await;
+            pool.included(&["original"]).await;
+
+            // the included transaction is sent again
+            assert!(!pool.send("included", raw).await, "included transaction must be rejected");
+        },
+        Scenario::Dropped => {
+            pool.send("original", pool.transfer(1, price)).await;
+            pool.send("replacement", pool.transfer(1, price * 2)).await;
+            pool.send("first", pool.transfer(0, price)).await;
+
+            assert_eq!(pool.wait("original").await, Err(DROPPED.to_string()));
+            assert_eq!(pool.wait("replacement").await, Ok(()));
+            // airdrop_to_address does not map the dropped transaction to the error
+            assert_eq!(pool.confirm("original").await, Ok(()));
+        },
+    }
+
+    pool.log
+}
+
+/// The scenario is executed on geth and proxy with new senders, the observations must match
+#[rstest(
+    scenario,
+    case::nonce_gap(Scenario::NonceGap),
+    case::replacement(Scenario::Replacement),
+    case::underpriced(Scenario::Underpriced),
+    case::duplicate(Scenario::Duplicate),
+    case::dropped(Scenario::Dropped),
+)]
+#[serial]
+async fn mempool(scenario: Scenario) {
+    let total_start = Instant::now();
+
+    let geth = run(scenario, "geth").await;
+    let proxy = run(scenario, "proxy").await;
+    assert_eq!(proxy, geth, "mempool behaviour of proxy differs from geth");
+
+    println!(
+        "[ {:.2}s ] - Total duration\n",
+        total_start.elapsed().as_secs_f64()
+    );
+}
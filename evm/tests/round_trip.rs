@@ -0,0 +1,158 @@
+mod shared;
+
+use ethers::types::Address;
+use ethers_core::types::{H256, U256};
+use ethers_signers::Signer as EthSigner;
+use rstest::*;
+use shared::{
+    client::Client,
+    fixture::{client, Deposit},
+    ledger::{to_lamports, to_wei, Ledger, OperatorBalances},
+    test_account,
+    utils::{return_current_provider, run_on_devnet, run_on_testnet, solana_rpc_url},
+    wallet, WITHDRAWAL_ADDRESS,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use std::{env, str::FromStr};
+
+/// Upper bound of the solana fees and rent paid by the depositor for one deposit, lamports
+const SOLANA_COSTS_LIMIT: u64 = 10_000_000;
+/// Balances taken before the uniswap run, ci/records is mounted to /opt/records
+const OPERATOR_BALANCES: &str = "/opt/records/operator_balances.json";
+
+/// Value moves from Solana to the rollup and back:
+///  1. deposit to alice
+///  2. transfer alice -> bob, half of it back bob -> alice
+///  3. withdrawal from alice and bob to the solana account
+///
+/// The circulating value is the sum of the user balances, the fee recipient balances and the
+/// lamports withdrawn to Solana. It only changes by the deposited amount.
+/// The predeployed withdrawal contract keeps the withdrawn value.
+#[rstest(
+    zero_gas,
+    deposit,
+    transfer,
+    withdrawal,
+    case::fee(false, U256::exp10(19), U256::exp10(18) * 3, U256::exp10(18)),
+    case::fee_free(true, U256::exp10(19), U256::exp10(18) * 3, U256::exp10(18)),
+)]
+#[serial_test::serial]
+async fn round_trip(zero_gas: bool, deposit: U256, transfer: U256, withdrawal: U256) {
+    if run_on_testnet() || run_on_devnet() {
+        println!("Run on testnet or devnet");
+        return;
+    }
+    assert!((withdrawal % to_wei(1)).is_zero(), "withdrawal must be a whole number of lamports");
+
+    let client = client(zero_gas);
+    let alice = wallet();
+    let bob = wallet();
+    let predeployed = Address::from_str(WITHDRAWAL_ADDRESS).unwrap();
+    let depositor = client.user_solana_wallet.pubkey();
+    let target = test_account();
+
+    let users = vec![alice.address(), bob.address()];
+    let fee_recipients = Client::get_fee_addresses(zero_gas);
+    let mut evm = [users.clone(), fee_recipients.clone()].concat();
+    evm.push(predeployed);
+    let solana = vec![depositor, target];
+
+    let circulating = |ledger: &Ledger| -> U256 {
+        ledger.evm_sum(&users) + ledger.evm_sum(&fee_recipients) + ledger.solana_sum(&[target])
+    };
+    let initial = Ledger::take(&client, &evm, &solana).await.unwrap();
+
+    // step 1: deposit
+    let rlp = Deposit::new(alice.address())
+        .value(deposit)
+        .mint(Some(deposit))
+        .source_hash(H256::random())
+        .rlp();
+    client.deposit(rlp.as_ref(), &client.user_solana_wallet).await.unwrap();
+    let deposited = Ledger::take(&client, &evm, &solana).await.unwrap();
+
+    assert_eq!(deposited.balance(&alice.address()), initial.balance(&alice.address()) + deposit);
+    assert_eq!(circulating(&deposited), circulating(&initial) + deposit);
+    assert_eq!(deposited.evm_sum(&fee_recipients), initial.evm_sum(&fee_recipients));
+    assert_eq!(deposited.balance(&predeployed), initial.balance(&predeployed));
+
+    let paid = initial.lamports(&depositor) - deposited.lamports(&depositor);
+    assert!(paid >= to_lamports(deposit));
+    assert!(paid - to_lamports(deposit) <= SOLANA_COSTS_LIMIT);
+
+    // step 2: transfers
+    let mut before = deposited;
+    for (from, to, value) in [(&alice, &bob, transfer), (&bob, &alice, transfer / 2)] {
+        client.transfer(from, &to.address(), value).await;
+        let after = Ledger::take(&client, &evm, &solana).await.unwrap();
+        let fee = after.evm_sum(&fee_recipients) - before.evm_sum(&fee_recipients);
+
+        assert_eq!(after.balance(&from.address()), before.balance(&from.address()) - value - fee);
+        assert_eq!(after.balance(&to.address()), before.balance(&to.address()) + value);
+        assert_eq!(after.balance(&predeployed), before.balance(&predeployed));
+        assert_eq!(circulating(&after), circulating(&before));
+        assert_eq!(fee.is_zero(), zero_gas);
+
+        before = after;
+    }
+
+    // step 3: withdrawals
+    for user in [&alice, &bob] {
+        client
+            .raw_call(&predeployed, "withdrawal(bytes32)", user, withdrawal, 2, target.to_bytes())
+            .await;
+        let after = Ledger::take(&client, &evm, &solana).await.unwrap();
+        let fee = after.evm_sum(&fee_recipients) - before.evm_sum(&fee_recipients);
+
+        assert_eq!(after.balance(&user.address()), before.balance(&user.address()) - withdrawal - fee);
+        assert_eq!(after.balance(&predeployed), before.balance(&predeployed) + withdrawal);
+        assert_eq!(after.lamports(&target), before.lamports(&target) + to_lamports(withdrawal));
+        assert_eq!(circulating(&after), circulating(&before));
+        assert_eq!(fee.is_zero(), zero_gas);
+
+        before = after;
+    }
+
+    // whole scenario
+    let fees = before.evm_sum(&fee_recipients) - initial.evm_sum(&fee_recipients);
+    let withdrawn = withdrawal * 2;
+
+    assert_eq!(circulating(&before), circulating(&initial) + deposit);
+    assert_eq!(before.evm_sum(&users), initial.evm_sum(&users) + deposit - withdrawn - fees);
+    assert_eq!(before.balance(&predeployed), initial.balance(&predeployed) + withdrawn);
+    assert_eq!(
+        before.solana_sum(&[target]),
+        initial.solana_sum(&[target]) + withdrawn,
+    );
+}
+
+async fn operator_balances() -> OperatorBalances {
+    let operator = Pubkey::from_str(&env::var("OPERATOR").expect("OPERATOR is not set")).unwrap();
+    let fee_recipient = Address::from_str(&env::var("FEE_RECIPIENT").expect("FEE_RECIPIENT is not set")).unwrap();
+    let solana = RpcClient::new(solana_rpc_url().to_string());
+
+    OperatorBalances::take(&solana, &return_current_provider("proxy"), &operator, fee_recipient)
+        .await
+        .unwrap()
+}
+
+/// Run by the uniswap scripts before the run: balances of the OPERATOR on Solana
+/// and of the FEE_RECIPIENT on the rollup are saved to OPERATOR_BALANCES
+#[rstest]
+#[ignore = "run by ci/scripts/economic_tests.sh"]
+async fn operator_balances_before() {
+    operator_balances().await.save(OPERATOR_BALANCES);
+}
+
+/// Run by the uniswap scripts after the run: the lamports spent by the operator are compared
+/// with the fee received by the fee recipient. The difference is reported, the run is not failed
+#[rstest]
+#[ignore = "run by ci/scripts/economic_tests.sh"]
+async fn operator_balances_after() {
+    let before = OperatorBalances::load(OPERATOR_BALANCES);
+    let after = operator_balances().await;
+    assert_eq!(before.operator, after.operator, "balances of another operator are saved");
+
+    before.report(&after);
+}
@@ -0,0 +1,38 @@
+mod shared;
+
+use ethers_core::types::U256;
+use ethers_signers::Signer;
+use rstest::*;
+use serial_test::serial;
+use shared::{
+    rpc::{RpcContext, RpcMatrix},
+    utils::{airdrop_to_address, get_random_wallet, return_current_provider},
+};
+use std::time::Instant;
+
+/// Every standard eth_*, net_* and web3_* method is called on proxy and geth.
+/// The matrix is written to RPC_MATRIX, only the required cases fail the test
+#[rstest]
+#[serial]
+async fn rpc_conformance() -> Result<(), Box<dyn std::error::Error>> {
+    let total_start = Instant::now();
+
+    let sender = get_random_wallet();
+    airdrop_to_address(sender.address(), U256::exp10(18), "proxy").await?;
+    let ctx = RpcContext::prepare("HelloWorld", "proxy", &sender).await;
+
+    let proxy = return_current_provider("proxy");
+    let geth = return_current_provider("geth");
+    let matrix = RpcMatrix::run(&proxy, &geth, &ctx).await;
+    matrix.write();
+
+    println!("[ Rpc ] - methods missing in proxy: {:?}", matrix.missing());
+    let failures = matrix.failures();
+    assert!(failures.is_empty(), "proxy differs from geth: {:#?}", failures);
+
+    println!(
+        "[ {:.2}s ] - Total duration\n",
+        total_start.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
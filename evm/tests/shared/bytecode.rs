@@ -0,0 +1,54 @@
+/// Maximum size of the deployed code, EIP-170
+#[allow(dead_code)]
+pub const MAX_CODE_SIZE: usize = 0x6000;
+/// Maximum size of the init code, EIP-3860
+#[allow(dead_code)]
+pub const MAX_INITCODE_SIZE: usize = 2 * MAX_CODE_SIZE;
+
+/// Value returned by the generated contract
+#[allow(dead_code)]
+pub const RETURN_VALUE: u8 = 42;
+
+/// Runtime code of the given size: returns RETURN_VALUE as uint256,
+/// the tail is filled with JUMPDEST
+#[allow(dead_code)]
+pub fn runtime_code(size: usize) -> Vec<u8> {
+    let mut code = vec![
+        0x60, RETURN_VALUE, // PUSH1 42
+        0x60, 0x00,         // PUSH1 0
+        0x52,               // MSTORE
+        0x60, 0x20,         // PUSH1 32
+        0x60, 0x00,         // PUSH1 0
+        0xf3,               // RETURN
+    ];
+    assert!(size >= code.len(), "runtime code is at least {} bytes", code.len());
+    code.resize(size, 0x5b);
+    code
+}
+
+/// Init code deploying the runtime code. The constructor arguments are appended to the init code
+/// and ignored, they only increase the calldata of the deployment
+#[allow(dead_code)]
+pub fn init_code(runtime: &[u8], ctor: &[u8]) -> Vec<u8> {
+    let len = u16::try_from(runtime.len()).expect("runtime code is too large");
+    let [hi, lo] = len.to_be_bytes();
+
+    let mut code = vec![
+        0x61, hi, lo, // PUSH2 len
+        0x80,         // DUP1
+        0x60, 0x0c,   // PUSH1 offset of the runtime code
+        0x60, 0x00,   // PUSH1 0
+        0x39,         // CODECOPY
+        0x60, 0x00,   // PUSH1 0
+        0xf3,         // RETURN
+    ];
+    code.extend_from_slice(runtime);
+    code.extend_from_slice(ctor);
+    code
+}
+
+/// Calldata of the given size, the generated contract ignores it
+#[allow(dead_code)]
+pub fn payload(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i % 251) as u8 + 1).collect()
+}
@@ -8,13 +8,16 @@ use rome_sdk::rome_solana::indexers::clock::SolanaClockIndexer;
 use rome_sdk::rome_solana::payer::SolanaKeyPayer;
 use rome_sdk::rome_solana::tower::SolanaTower;
 use rome_sdk::rome_solana::types::AsyncAtomicRpcClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
+use solana_sdk::signature::{Keypair, Signature};
 use ethers_core::{k256::ecdsa::SigningKey, types::Address};
 use ethers_core::types::{
     transaction::eip2718::TypedTransaction, U256, H160,
 };
 use super::{config::Config, client_config, tx::{do_tx, do_rlp}};
+use super::resources::{load_report, TxReport};
 use ethers_signers::{Signer as EthSigner, Wallet};
 use rome_sdk::rome_evm_client::Payer;
 
@@ -38,6 +41,10 @@ pub struct Client {
     pub user_solana_wallet: Keypair,
     /// user's rome wallet
     pub user_wallet: Wallet<SigningKey>,
+    /// rome-evm program id
+    pub program_id: Pubkey,
+    /// solana rpc client to load the transactions produced by the EVM transactions
+    pub solana_rpc: RpcClient,
 }
 
 impl Deref for Client {
@@ -78,6 +85,10 @@ impl Client {
         };
 
         let rpc_client: AsyncAtomicRpcClient = config.solana.clone().into_async_client().into();
+        let solana_rpc = RpcClient::new_with_commitment(
+            config.solana.rpc_url.to_string(),
+            CommitmentConfig::confirmed(),
+        );
 
         let solana_clock_indexer = SolanaClockIndexer::new(rpc_client.clone())
             .await
@@ -103,7 +114,9 @@ impl Client {
             sdk_client,
             upgrade_authority: upgrade_authority,
             user_solana_wallet: user_solana_wallet,
-            user_wallet
+            user_wallet,
+            program_id,
+            solana_rpc,
         }
     }
 
@@ -113,11 +126,30 @@ impl Client {
         tx: &TypedTransaction,
         wallet: &Wallet<SigningKey>,
     ) {
+        self.send_and_check(tx, wallet).await;
+    }
+
+    /// [Client::send_tx] that also loads the solana transactions by the signatures returned by the sdk
+    #[allow(dead_code)]
+    pub async fn send_tx_with_report(
+        &self,
+        tx: &TypedTransaction,
+        wallet: &Wallet<SigningKey>,
+    ) -> TxReport {
+        let signatures = self.send_and_check(tx, wallet).await;
+        load_report(&self.solana_rpc, &self.program_id, &signatures).await
+    }
+
+    async fn send_and_check(
+        &self,
+        tx: &TypedTransaction,
+        wallet: &Wallet<SigningKey>,
+    ) -> Vec<Signature> {
         let rlp = do_rlp(tx, wallet);
         let from = wallet.address();
         let initial = self.get_balance(from).unwrap();
 
-        self.send_transaction(rlp.into()).await.unwrap();
+        let signatures = self.send_transaction(rlp.into()).await.unwrap();
 
         let actual= self.get_balance(from).unwrap();
         let transfer = initial.checked_sub(actual).unwrap();
@@ -129,6 +161,23 @@ impl Client {
             assert!(transfer > U256::zero());
             assert!(balance > U256::zero());
         }
+
+        signatures
+    }
+
+    /// Sign and send transaction without any checks, the result of send_transaction is returned
+    #[allow(dead_code)]
+    pub async fn try_send_tx(
+        &self,
+        tx: &TypedTransaction,
+        wallet: &Wallet<SigningKey>,
+    ) -> anyhow::Result<()> {
+        let rlp = do_rlp(tx, wallet);
+
+        self.send_transaction(rlp.into())
+            .await
+            .map(|_| ())
+            .map_err(|e| anyhow::anyhow!("{:?}", e))
     }
 
     /// Deploy contract
@@ -163,10 +212,37 @@ impl Client {
         value: U256,
         tx_type: u8,
     ) {
+        let tx = self.method_tx(contract, address, method, wallet, value, tx_type);
+        self.send_tx(&tx, wallet).await;
+    }
+
+    /// [Client::method_call] with the report of the solana transactions
+    #[allow(dead_code)]
+    pub async fn method_call_with_report (
+        &self,
+        contract: &String,
+        address: &Address,
+        method: &str,
+        wallet: &Wallet<SigningKey>,
+        value: U256,
+        tx_type: u8,
+    ) -> TxReport {
+        let tx = self.method_tx(contract, address, method, wallet, value, tx_type);
+        self.send_tx_with_report(&tx, wallet).await
+    }
+
+    fn method_tx(
+        &self,
+        contract: &String,
+        address: &Address,
+        method: &str,
+        wallet: &Wallet<SigningKey>,
+        value: U256,
+        tx_type: u8,
+    ) -> TypedTransaction {
         let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
         let call_data = method_id(&abi, method);
-        let tx = do_tx(self, Some(*address), call_data, &wallet, value, tx_type);
-        self.send_tx(&tx, wallet).await;
+        do_tx(self, Some(*address), call_data, &wallet, value, tx_type)
     }
 
     #[allow(dead_code)]
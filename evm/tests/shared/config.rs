@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use ethers_core::types::H160;
 use rome_sdk::{
     rome_solana::config::SolanaConfig, rome_evm_client::PayerConfig,
 };
@@ -18,6 +19,20 @@ pub struct Config {
     pub start_slot: Option<u64>,
 }
 
+/// Payers of the client config file. The fields of [PayerConfig] are not public,
+/// the same file is loaded to this struct by [load_config]
+#[derive(serde::Deserialize, Debug)]
+pub struct PayersConfig {
+    pub payers: Vec<PayerKeys>,
+}
+
+/// Payer keypair and fee recipients of [PayerConfig]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct PayerKeys {
+    pub payer_keypair: PathBuf,
+    pub fee_recipients: Option<Vec<H160>>,
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug)]
 pub struct Keys<'a> {
     pub sender_public_key: Cow<'a, str>,
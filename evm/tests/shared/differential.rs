@@ -0,0 +1,368 @@
+use std::fs;
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, Provider, RpcError};
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{BlockId, Bytes, TransactionReceipt, TransactionRequest, H160, H256, U256};
+use ethers_signers::Wallet;
+use proptest::prelude::*;
+use super::{
+    bytecode::init_code,
+    receipt::ReceiptComparator,
+    utils::{deploy_bytecode, return_current_provider, wait_for_inclusion},
+};
+
+/// Regression cases of the comparison suite, ci/regressions/bytecode.txt of the repository
+#[allow(dead_code)]
+pub const REGRESSIONS: &str = "/opt/ci/regressions/bytecode.txt";
+
+/// Gas limit of the deployment and the execution
+const GAS: u64 = 1_000_000;
+/// Storage slots used by the programs
+const SLOTS: u8 = 8;
+/// Stack depth limit of the generated code, below the EVM limit of 1024
+const MAX_DEPTH: usize = 1000;
+
+/// Opcodes operating on the stack only: opcode, inputs, outputs
+const STACK_OPS: &[(u8, usize, usize)] = &[
+    (0x01, 2, 1), // ADD
+    (0x02, 2, 1), // MUL
+    (0x03, 2, 1), // SUB
+    (0x04, 2, 1), // DIV
+    (0x05, 2, 1), // SDIV
+    (0x06, 2, 1), // MOD
+    (0x07, 2, 1), // SMOD
+    (0x08, 3, 1), // ADDMOD
+    (0x09, 3, 1), // MULMOD
+    (0x0a, 2, 1), // EXP
+    (0x0b, 2, 1), // SIGNEXTEND
+    (0x10, 2, 1), // LT
+    (0x11, 2, 1), // GT
+    (0x12, 2, 1), // SLT
+    (0x13, 2, 1), // SGT
+    (0x14, 2, 1), // EQ
+    (0x15, 1, 1), // ISZERO
+    (0x16, 2, 1), // AND
+    (0x17, 2, 1), // OR
+    (0x18, 2, 1), // XOR
+    (0x19, 1, 1), // NOT
+    (0x1a, 2, 1), // BYTE
+    (0x1b, 2, 1), // SHL
+    (0x1c, 2, 1), // SHR
+    (0x1d, 2, 1), // SAR
+    (0x50, 1, 0), // POP
+    (0x80, 1, 2), // DUP1
+    (0x81, 2, 3), // DUP2
+    (0x90, 2, 2), // SWAP1
+    (0x91, 3, 3), // SWAP2
+];
+
+/// Opcodes pushing the execution context
+const CONTEXT_OPS: &[u8] = &[
+    0x30, // ADDRESS
+    0x32, // ORIGIN
+    0x33, // CALLER
+    0x34, // CALLVALUE
+    0x36, // CALLDATASIZE
+    0x38, // CODESIZE
+    0x3d, // RETURNDATASIZE
+    0x42, // TIMESTAMP
+    0x43, // NUMBER
+    0x45, // GASLIMIT
+    0x46, // CHAINID
+    0x47, // SELFBALANCE
+    0x48, // BASEFEE
+    0x58, // PC
+    0x59, // MSIZE
+    0x5f, // PUSH0
+];
+
+/// Part of the program with the known stack effect. The memory offsets and the storage keys are bounded
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Snippet {
+    /// PUSH1..PUSH32
+    Push(Vec<u8>),
+    /// index of STACK_OPS
+    Op(usize),
+    /// index of CONTEXT_OPS
+    Context(usize),
+    MStore(u8),
+    MLoad(u8),
+    SStore(u8),
+    SLoad(u8),
+    CallDataLoad(u8),
+    /// LOG0..LOG2 of memory 0..32
+    Log(u8),
+}
+
+impl Snippet {
+    /// Number of the stack inputs and outputs
+    fn effect(&self) -> (usize, usize) {
+        match self {
+            Snippet::Op(i) => (STACK_OPS[*i].1, STACK_OPS[*i].2),
+            Snippet::MStore(_) | Snippet::SStore(_) => (1, 0),
+            Snippet::Log(topics) => (*topics as usize, 0),
+            _ => (0, 1),
+        }
+    }
+
+    fn code(&self) -> Vec<u8> {
+        match self {
+            Snippet::Push(bytes) => [vec![0x5f + bytes.len() as u8], bytes.clone()].concat(),
+            Snippet::Op(i) => vec![STACK_OPS[*i].0],
+            Snippet::Context(i) => vec![CONTEXT_OPS[*i]],
+            Snippet::MStore(offset) => vec![0x60, *offset, 0x52],
+            Snippet::MLoad(offset) => vec![0x60, *offset, 0x51],
+            Snippet::SStore(key) => vec![0x60, key % SLOTS, 0x55],
+            Snippet::SLoad(key) => vec![0x60, key % SLOTS, 0x54],
+            Snippet::CallDataLoad(offset) => vec![0x60, *offset, 0x35],
+            Snippet::Log(topics) => vec![0x60, 0x20, 0x60, 0x00, 0xa0 + topics],
+        }
+    }
+}
+
+/// The last instruction of the program
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Terminal {
+    /// returns memory 0..32
+    Return,
+    Stop,
+    /// reverts with memory 0..32
+    Revert,
+    Invalid,
+}
+
+impl Terminal {
+    fn code(&self) -> Vec<u8> {
+        match self {
+            Terminal::Return => vec![0x60, 0x20, 0x60, 0x00, 0xf3],
+            Terminal::Stop => vec![0x00],
+            Terminal::Revert => vec![0x60, 0x20, 0x60, 0x00, 0xfd],
+            Terminal::Invalid => vec![0xfe],
+        }
+    }
+}
+
+/// Random program and the calldata of the call
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Program {
+    pub snippets: Vec<Snippet>,
+    pub terminal: Terminal,
+    pub calldata: Vec<u8>,
+}
+
+#[allow(dead_code)]
+impl Program {
+    /// Runtime code. The missing stack inputs are pushed as zeros,
+    /// the snippets increasing the stack above MAX_DEPTH are skipped
+    pub fn code(&self) -> Vec<u8> {
+        let mut code = vec![];
+        let mut depth = 0;
+
+        for snippet in &self.snippets {
+            let (inputs, outputs) = snippet.effect();
+            if depth.max(inputs) - inputs + outputs > MAX_DEPTH {
+                continue;
+            }
+            while depth < inputs {
+                code.push(0x5f); // PUSH0
+                depth += 1;
+            }
+            code.extend(snippet.code());
+            depth = depth - inputs + outputs;
+        }
+
+        code.extend(self.terminal.code());
+        code
+    }
+
+    /// The program without the snippet
+    pub fn without(&self, index: usize) -> Self {
+        let mut program = self.clone();
+        program.snippets.remove(index);
+        program
+    }
+
+    /// Line of the regression file: name, hex of the runtime code, hex of the calldata
+    pub fn regression(&self, name: &str) -> String {
+        format!("{} {} {}", name, hex::encode(self.code()), hex::encode(&self.calldata))
+    }
+}
+
+/// Regression case: the runtime code and the calldata
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Regression {
+    pub name: String,
+    pub code: Vec<u8>,
+    pub calldata: Vec<u8>,
+}
+
+/// Regression cases of the file, the empty lines and the comments (#) are skipped.
+/// The missing file is an error, the replay must not pass without the cases
+#[allow(dead_code)]
+pub fn regressions(path: &str) -> Vec<Regression> {
+    let content = fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e));
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            assert!(parts.len() == 2 || parts.len() == 3, "invalid regression: {}", line);
+            Regression {
+                name: parts[0].to_string(),
+                code: hex::decode(parts[1]).expect("invalid code"),
+                calldata: parts.get(2).map(|x| hex::decode(x).expect("invalid calldata")).unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Print the minimized program in the format of ci/regressions/bytecode.txt,
+/// the line is kept in the records of the run and is added to the file by hand
+#[allow(dead_code)]
+pub fn save_regression(program: &Program, name: &str) {
+    println!("[ Differential ] - regression: {}", program.regression(name));
+}
+
+fn snippet() -> impl Strategy<Value = Snippet> {
+    prop_oneof![
+        4 => prop::collection::vec(any::<u8>(), 1..=32).prop_map(Snippet::Push),
+        6 => (0..STACK_OPS.len()).prop_map(Snippet::Op),
+        2 => (0..CONTEXT_OPS.len()).prop_map(Snippet::Context),
+        1 => any::<u8>().prop_map(Snippet::MStore),
+        1 => any::<u8>().prop_map(Snippet::MLoad),
+        1 => any::<u8>().prop_map(Snippet::SStore),
+        1 => any::<u8>().prop_map(Snippet::SLoad),
+        1 => any::<u8>().prop_map(Snippet::CallDataLoad),
+        1 => (0u8..=2).prop_map(Snippet::Log),
+    ]
+}
+
+/// Valid opcode sequences with the bounded stack effect
+#[allow(dead_code)]
+pub fn program() -> impl Strategy<Value = Program> {
+    let terminal = prop_oneof![
+        4 => Just(Terminal::Return),
+        1 => Just(Terminal::Stop),
+        1 => Just(Terminal::Revert),
+        1 => Just(Terminal::Invalid),
+    ];
+
+    (
+        prop::collection::vec(snippet(), 1..64),
+        terminal,
+        prop::collection::vec(any::<u8>(), 0..64),
+    )
+        .prop_map(|(snippets, terminal, calldata)| Program { snippets, terminal, calldata })
+}
+
+/// Deploy the runtime code through the proxy and call it.
+/// Returns the address of the contract and the receipt of the call
+#[allow(dead_code)]
+pub async fn execute(
+    code: &[u8],
+    calldata: &[u8],
+    sender: &Wallet<SigningKey>,
+) -> Result<(H160, TransactionReceipt), Box<dyn std::error::Error>> {
+    let (address, receipt) = deploy_bytecode(init_code(code, &[]).into(), "proxy", sender, Some(GAS.into())).await?;
+    if receipt.status != Some(1.into()) {
+        return Err("deployment failed".into());
+    }
+
+    let client = SignerMiddleware::new(return_current_provider("proxy"), sender.clone());
+    let tx = TransactionRequest::new().to(address).data(calldata.to_vec()).gas(GAS);
+    let receipt = wait_for_inclusion(client.send_transaction(tx, None).await?).await?;
+    Ok((address, receipt))
+}
+
+/// Result of eth_call: the return data or the error code and data
+async fn call(provider: &Provider<Http>, tx: &TransactionRequest, block: BlockId) -> String {
+    match provider.call(&tx.clone().into(), Some(block)).await {
+        Ok(data) => format!("ok {}", data),
+        Err(e) => match e.as_error_response() {
+            Some(e) => format!("error {} {:?}", e.code, e.data),
+            None => format!("transport {}", e),
+        },
+    }
+}
+
+/// Compare the execution on proxy and geth: the receipt (revert status, gas, logs),
+/// the storage and the return data of eth_call at the block of the execution
+#[allow(dead_code)]
+pub async fn compare(address: H160, calldata: &[u8], receipt: &TransactionReceipt) -> Vec<String> {
+    let proxy = return_current_provider("proxy");
+    let geth = return_current_provider("geth");
+    let mut diffs = vec![];
+
+    let proxy_receipt = proxy.get_transaction_receipt(receipt.transaction_hash).await.unwrap();
+    let geth_receipt = geth.get_transaction_receipt(receipt.transaction_hash).await.unwrap();
+    match (proxy_receipt, geth_receipt) {
+        (Some(proxy_receipt), Some(geth_receipt)) => {
+            diffs.extend(ReceiptComparator::op_stack().compare(&proxy_receipt, &geth_receipt));
+        },
+        (proxy_receipt, geth_receipt) => {
+            diffs.push(format!("receipt: proxy {:?}, geth {:?}", proxy_receipt.is_some(), geth_receipt.is_some()));
+        },
+    }
+
+    let block: BlockId = receipt.block_number.unwrap().into();
+    for slot in 0..SLOTS {
+        let slot = H256::from_low_u64_be(slot as u64);
+        let proxy_value = proxy.get_storage_at(address, slot, Some(block)).await.unwrap();
+        let geth_value = geth.get_storage_at(address, slot, Some(block)).await.unwrap();
+        if proxy_value != geth_value {
+            diffs.push(format!("storage {:?}: proxy {:?}, geth {:?}", slot, proxy_value, geth_value));
+        }
+    }
+
+    let tx = TransactionRequest::new()
+        .from(receipt.from)
+        .to(address)
+        .data(Bytes::from(calldata.to_vec()))
+        .gas(U256::from(GAS));
+    let proxy_result = call(&proxy, &tx, block).await;
+    let geth_result = call(&geth, &tx, block).await;
+    if proxy_result != geth_result {
+        diffs.push(format!("eth_call: proxy {}, geth {}", proxy_result, geth_result));
+    }
+
+    for diff in &diffs {
+        println!("[ Differential ] - differs {}", diff);
+    }
+    diffs
+}
+
+/// Execute the program and compare the results
+#[allow(dead_code)]
+pub async fn run(program: &Program, sender: &Wallet<SigningKey>) -> Vec<String> {
+    let code = program.code();
+    match execute(&code, &program.calldata, sender).await {
+        Ok((address, receipt)) => compare(address, &program.calldata, &receipt).await,
+        Err(e) => vec![format!("execution failed: {}", e)],
+    }
+}
+
+/// Remove the snippets one by one while the program still differs, at most `runs` executions
+#[allow(dead_code)]
+pub async fn minimize(program: &Program, sender: &Wallet<SigningKey>, runs: usize) -> Program {
+    let mut program = program.clone();
+    let mut runs = runs;
+    let mut index = program.snippets.len();
+
+    while index > 0 && runs > 0 {
+        index -= 1;
+        let candidate = program.without(index);
+        runs -= 1;
+
+        if !run(&candidate, sender).await.is_empty() {
+            println!("[ Differential ] - snippet {:?} removed", program.snippets[index]);
+            program = candidate;
+        }
+    }
+
+    program
+}
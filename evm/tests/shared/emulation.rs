@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{transaction::eip2718::TypedTransaction, H160, H256, U256};
+use ethers_signers::Wallet;
+use rome_sdk::rome_evm_client::emulator::{Emulation, Instruction};
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use super::{client::Client, tx::do_rlp};
+
+/// Log emitted during the emulation
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmulatedLog {
+    pub address: H160,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+/// Emulation report of rome-evm converted to the ethers types
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct EmulationReport {
+    /// solana accounts required by the transaction and their writable flags
+    pub accounts: BTreeMap<Pubkey, bool>,
+    /// storage slots touched by the transaction: contract -> slot -> is written
+    pub storage: BTreeMap<H160, BTreeMap<U256, bool>>,
+    /// gas estimate
+    pub gas: U256,
+    /// number of the EVM instructions executed
+    pub steps: u64,
+    pub return_data: Vec<u8>,
+    pub logs: Vec<EmulatedLog>,
+    /// the transaction fits a single solana transaction
+    pub is_atomic: bool,
+    /// status of the receipt: false if the execution reverts or fails
+    pub status: bool,
+}
+
+#[allow(dead_code)]
+impl EmulationReport {
+    pub fn new(emulation: &Emulation) -> Self {
+        let accounts = emulation
+            .accounts
+            .iter()
+            .map(|(key, state)| (*key, state.is_writable))
+            .collect();
+
+        let storage = emulation
+            .storage
+            .iter()
+            .map(|(address, slots)| {
+                let slots = slots
+                    .iter()
+                    .map(|(slot, rw)| {
+                        let mut buf = [0u8; 32];
+                        slot.to_big_endian(&mut buf);
+                        (U256::from_big_endian(&buf), *rw)
+                    })
+                    .collect();
+                (H160::from_slice(address.as_bytes()), slots)
+            })
+            .collect();
+
+        let vm = emulation.vm.as_ref().expect("emulation vm state not found");
+        let logs = vm
+            .logs
+            .iter()
+            .map(|log| EmulatedLog {
+                address: H160::from_slice(log.address.as_bytes()),
+                topics: log.topics.iter().map(|t| H256::from_slice(t.as_bytes())).collect(),
+                data: log.data.to_vec(),
+            })
+            .collect();
+
+        Self {
+            accounts,
+            storage,
+            gas: U256::from(emulation.gas),
+            steps: emulation.steps_executed,
+            return_data: vm.return_value.clone().unwrap_or_default(),
+            logs,
+            is_atomic: emulation.is_atomic,
+            status: vm.exit_reason.as_ref().map_or(false, |reason| reason.is_succeed()),
+        }
+    }
+
+    /// Slots of the contract: slot -> is written
+    pub fn slots(&self, address: &H160) -> BTreeMap<U256, bool> {
+        self.storage.get(address).cloned().unwrap_or_default()
+    }
+
+    pub fn writable_accounts(&self) -> Vec<Pubkey> {
+        self.accounts
+            .iter()
+            .filter(|(_, writable)| **writable)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+}
+
+/// Emulate DoTx instruction of the signed transaction
+#[allow(dead_code)]
+pub async fn emulate_tx(
+    client: &Client,
+    tx: &TypedTransaction,
+    wallet: &Wallet<SigningKey>,
+) -> EmulationReport {
+    let mut tx_data = vec![0]; // Option<fee_recipient>
+    tx_data.append(&mut do_rlp(tx, wallet));
+
+    let resource = client.tx_builder().lock_resource().await.unwrap();
+    let emulation = client
+        .emulate(Instruction::DoTx, &tx_data, &resource.payer().pubkey())
+        .unwrap();
+
+    EmulationReport::new(&emulation)
+}
@@ -0,0 +1,198 @@
+use std::collections::{BTreeMap, BTreeSet};
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{transaction::eip2718::TypedTransaction, H160, U256};
+use ethers_signers::{Signer, Wallet};
+use rome_sdk::rome_solana::payer::SolanaKeyPayer;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as SolanaSigner};
+use super::{
+    client::Client,
+    config::{load_config, PayersConfig},
+    fixture::cfg_path,
+};
+
+/// Fee recipients of the payers of the client config
+#[allow(dead_code)]
+pub async fn payer_fee_recipients(zero_gas: bool) -> BTreeMap<Pubkey, Vec<H160>> {
+    let config: PayersConfig = load_config(cfg_path(zero_gas)).unwrap();
+    let mut recipients = BTreeMap::new();
+
+    for payer in config.payers {
+        let keypair = SolanaKeyPayer::read_from_file(&payer.payer_keypair)
+            .await
+            .expect("read payer_keypair error")
+            .into_keypair();
+        recipients
+            .entry(keypair.pubkey())
+            .or_insert_with(Vec::new)
+            .extend(payer.fee_recipients.unwrap_or_default());
+    }
+
+    recipients
+}
+
+/// Who paid for the EVM transaction on Solana and who received the EVM fee
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct FeeRecord {
+    /// payers of the solana transactions
+    pub payers: BTreeSet<Pubkey>,
+    /// gas fee charged from the sender, wei
+    pub spent: U256,
+    /// fee recipients with the increased balance and the increase, wei
+    pub recipients: BTreeMap<H160, U256>,
+    /// lamports paid by the payers
+    pub lamports: u64,
+}
+
+#[allow(dead_code)]
+impl FeeRecord {
+    /// The single payer of the EVM transaction
+    pub fn payer(&self) -> Pubkey {
+        assert_eq!(self.payers.len(), 1, "solana transactions are paid by several payers: {:?}", self.payers);
+        *self.payers.first().unwrap()
+    }
+
+    /// The fee goes to one recipient of the payer and equals the gas fee charged from the sender.
+    /// fee_recipients: see [payer_fee_recipients]
+    pub fn check(&self, fee_recipients: &BTreeMap<Pubkey, Vec<H160>>) {
+        let payer = self.payer();
+        let expected = fee_recipients.get(&payer).expect("unknown payer");
+
+        if expected.is_empty() {
+            assert!(self.recipients.is_empty(), "fee-free payer {} has recipients: {:?}", payer, self.recipients);
+            assert_eq!(self.spent, U256::zero());
+            return;
+        }
+
+        assert_eq!(self.recipients.len(), 1, "fee is split between recipients: {:?}", self.recipients);
+        let (recipient, fee) = self.recipients.first_key_value().unwrap();
+        assert!(
+            expected.contains(recipient),
+            "fee recipient {:?} does not belong to payer {}, expected one of {:?}",
+            recipient,
+            payer,
+            expected,
+        );
+        assert_eq!(*fee, self.spent, "fee received by {:?} differs from the charged fee", recipient);
+    }
+}
+
+/// Sign and send the transaction, find the payer and the fee recipient
+#[allow(dead_code)]
+pub async fn send_with_fee(
+    client: &Client,
+    tx: &TypedTransaction,
+    wallet: &Wallet<SigningKey>,
+    fee_recipients: &BTreeMap<Pubkey, Vec<H160>>,
+) -> FeeRecord {
+    // a recipient shared by several payers is loaded once
+    let recipients: Vec<H160> = fee_recipients
+        .values()
+        .flatten()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let balances = |client: &Client| -> Vec<U256> {
+        recipients.iter().map(|a| client.get_balance(*a).unwrap()).collect()
+    };
+
+    let value = tx.value().copied().unwrap_or_default();
+    let sender_before = client.get_balance(wallet.address()).unwrap();
+    let before = balances(client);
+
+    let report = client.send_tx_with_report(tx, wallet).await;
+
+    let sender_after = client.get_balance(wallet.address()).unwrap();
+    let after = balances(client);
+
+    let recipients = recipients
+        .iter()
+        .zip(before.iter().zip(after))
+        .filter(|(_, (before, after))| after > *before)
+        .map(|(address, (before, after))| (*address, after - before))
+        .collect();
+
+    FeeRecord {
+        payers: report.payers(),
+        spent: sender_before - sender_after - value,
+        recipients,
+        lamports: report.lamports(),
+    }
+}
+
+/// Accepted probability that a payer or a fee recipient is not used by chance
+const MISS_PROBABILITY: f64 = 0.001;
+
+/// Number of transactions after which every one of the `count` choices is used,
+/// if the choice is uniform, except with [MISS_PROBABILITY]
+#[allow(dead_code)]
+pub fn min_txs(count: usize) -> usize {
+    if count <= 1 {
+        return count;
+    }
+    let miss = 1.0 - 1.0 / count as f64;
+    ((MISS_PROBABILITY / count as f64).ln() / miss.ln()).ceil() as usize
+}
+
+/// Fees of many EVM transactions by the payers and the fee recipients
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default)]
+pub struct FeeDistribution {
+    /// number of EVM transactions paid by the payer
+    pub payers: BTreeMap<Pubkey, usize>,
+    /// number of fees and the sum received by the recipient from the transactions of the payer,
+    /// a recipient shared by several payers has an entry per payer
+    pub recipients: BTreeMap<(Pubkey, H160), (usize, U256)>,
+    /// gas fee charged from the senders
+    pub spent: U256,
+}
+
+#[allow(dead_code)]
+impl FeeDistribution {
+    pub fn push(&mut self, record: &FeeRecord) {
+        let payer = record.payer();
+        *self.payers.entry(payer).or_default() += 1;
+        for (recipient, fee) in &record.recipients {
+            let entry = self.recipients.entry((payer, *recipient)).or_default();
+            entry.0 += 1;
+            entry.1 += *fee;
+        }
+        self.spent += record.spent;
+    }
+
+    /// The fees are not lost. The payers and the fee recipients are chosen uniformly:
+    /// every one of them is used once there are enough transactions, see [min_txs]
+    pub fn check(&self, fee_recipients: &BTreeMap<Pubkey, Vec<H160>>) {
+        println!("[ Fees ] - payers {:?}, recipients {:?}, spent {}", self.payers, self.recipients, self.spent);
+
+        let total: usize = self.payers.values().sum();
+        if total >= min_txs(fee_recipients.len()) {
+            for payer in fee_recipients.keys() {
+                assert!(self.payers.contains_key(payer), "payer {} is not used in {} transactions", payer, total);
+            }
+        } else {
+            println!("[ Fees ] - payers are not checked, {} transactions < {}", total, min_txs(fee_recipients.len()));
+        }
+
+        for (payer, count) in &self.payers {
+            let recipients = fee_recipients.get(payer).expect("unknown payer");
+            if *count < min_txs(recipients.len()) {
+                println!("[ Fees ] - recipients of {} are not checked, {} transactions < {}", payer, count, min_txs(recipients.len()));
+                continue;
+            }
+            for recipient in recipients {
+                assert!(
+                    self.recipients.contains_key(&(*payer, *recipient)),
+                    "fee recipient {:?} received nothing from {} transactions of {}",
+                    recipient,
+                    count,
+                    payer,
+                );
+            }
+        }
+
+        let received = self.recipients.values().fold(U256::zero(), |sum, (_, fee)| sum + fee);
+        assert_eq!(received, self.spent);
+    }
+}
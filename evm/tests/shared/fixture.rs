@@ -4,13 +4,29 @@ use ethers::prelude::*;
 use ethers_signers::{Signer as EthSigner, };
 use rand::prelude::*;
 use rstest::fixture;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use ethers_core::types::transaction::eip2718::TypedTransaction;
 use ethers_core::types::transaction::optimism::DepositTransaction;
 
 #[fixture]
 #[once]
 pub fn client(#[default(true)] zero_gas: bool) -> Arc<Client> {
+    new_client(zero_gas)
+}
+
+/// Client of the fee-free or the fee-paying rollup, both can be used by the same test binary.
+/// The client fixture keeps the first created client only
+#[allow(dead_code)]
+pub fn mode_client(zero_gas: bool) -> Arc<Client> {
+    static FEE_FREE: OnceLock<Arc<Client>> = OnceLock::new();
+    static FEE_PAYING: OnceLock<Arc<Client>> = OnceLock::new();
+
+    let cell = if zero_gas { &FEE_FREE } else { &FEE_PAYING };
+    cell.get_or_init(|| new_client(zero_gas)).clone()
+}
+
+/// Register a new rollup with the random chain_id and deposit to the genesis wallet
+pub fn new_client(zero_gas: bool) -> Arc<Client> {
     let runtime = tokio::runtime::Runtime::new().unwrap();
     let path = cfg_path(zero_gas);
     let mut config = client_config(path);
@@ -54,32 +70,141 @@ pub fn client(#[default(true)] zero_gas: bool) -> Arc<Client> {
 }
 
 pub fn rlp_0x7e(address: Address) -> Bytes {
+    Deposit::new(address).rlp()
+}
+
+/// Ways to break the RLP encoding of a deposit transaction
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub enum Malformation {
+    /// the last byte of the payload is cut off
+    Truncated,
+    /// an extra byte is appended after the payload
+    TrailingBytes,
+    /// the 0x7e type byte is replaced with an unknown one
+    UnknownType,
+    /// the payload is an RLP string instead of a list
+    NotAList,
+    /// only the type byte is sent
+    Empty,
+}
 
-    let tx = DepositTransaction {
-        tx: TransactionRequest {
-            from: Some(address),
-            to: Some(address.into()),
-            gas: Some(21000.into()),
-            gas_price: None,
-            value: Some(DEPOSIT_VALUE.into()),
+/// Deposit (0x7e) transaction.
+/// The default values match the deposit made by the [client] fixture
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Deposit {
+    pub from: Address,
+    pub to: Option<Address>,
+    pub gas: U256,
+    pub value: U256,
+    pub data: Option<Bytes>,
+    pub source_hash: H256,
+    pub mint: Option<U256>,
+    pub is_system_tx: bool,
+}
+
+#[allow(dead_code)]
+impl Deposit {
+    /// Deposit of [DEPOSIT_VALUE] from the address to itself
+    pub fn new(address: Address) -> Self {
+        Self {
+            from: address,
+            to: Some(address),
+            gas: 21000.into(),
+            value: DEPOSIT_VALUE.into(),
             data: None,
-            nonce: None,
-            chain_id: None,
-        },
-        source_hash: H256::zero(),
-        mint: Some(DEPOSIT_VALUE.into()),
-        is_system_tx: false,
-    };
-    let sig = Signature {
-        r: U256::default(),
-        s: U256::default(),
-        v: 0,
-    };
-
-    let typed_tx: TypedTransaction = tx.clone().into();
-    let rlp = typed_tx.rlp_signed(&sig);
-
-    rlp
+            source_hash: H256::zero(),
+            mint: Some(DEPOSIT_VALUE.into()),
+            is_system_tx: false,
+        }
+    }
+
+    /// Recipient of the deposit, None means a contract creation
+    pub fn to(mut self, to: Option<Address>) -> Self {
+        self.to = to;
+        self
+    }
+
+    pub fn gas(mut self, gas: U256) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    pub fn data(mut self, data: Vec<u8>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    pub fn source_hash(mut self, source_hash: H256) -> Self {
+        self.source_hash = source_hash;
+        self
+    }
+
+    /// Amount minted on the rollup, None means nothing is minted
+    pub fn mint(mut self, mint: Option<U256>) -> Self {
+        self.mint = mint;
+        self
+    }
+
+    pub fn system_tx(mut self, is_system_tx: bool) -> Self {
+        self.is_system_tx = is_system_tx;
+        self
+    }
+
+    /// Type byte followed by the RLP of the deposit transaction
+    pub fn rlp(&self) -> Bytes {
+        let tx = DepositTransaction {
+            tx: TransactionRequest {
+                from: Some(self.from),
+                to: self.to.map(|a| a.into()),
+                gas: Some(self.gas),
+                gas_price: None,
+                value: Some(self.value),
+                data: self.data.clone(),
+                nonce: None,
+                chain_id: None,
+            },
+            source_hash: self.source_hash,
+            mint: self.mint,
+            is_system_tx: self.is_system_tx,
+        };
+        // deposit transactions are not signed
+        let sig = Signature {
+            r: U256::default(),
+            s: U256::default(),
+            v: 0,
+        };
+
+        let typed_tx: TypedTransaction = tx.into();
+        typed_tx.rlp_signed(&sig)
+    }
+
+    /// RLP of the deposit transaction broken in the given way
+    pub fn malformed_rlp(&self, malformation: Malformation) -> Bytes {
+        let mut rlp = self.rlp().to_vec();
+
+        match malformation {
+            Malformation::Truncated => {
+                rlp.pop();
+            },
+            Malformation::TrailingBytes => rlp.push(0),
+            Malformation::UnknownType => rlp[0] = 0x7f,
+            Malformation::NotAList => {
+                let mut stream = ::rlp::RlpStream::new();
+                stream.append(&rlp[1..].to_vec());
+                rlp = [vec![0x7e], stream.out().to_vec()].concat();
+            },
+            Malformation::Empty => rlp.truncate(1),
+        }
+
+        rlp.into()
+    }
 }
 
 pub fn cfg_path (zero_gas: bool) -> &'static str {
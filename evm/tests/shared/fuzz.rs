@@ -0,0 +1,259 @@
+use std::{env, time::{Duration, Instant}};
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{
+    transaction::{eip2718::TypedTransaction, eip2930::{AccessList, AccessListItem}},
+    Eip1559TransactionRequest, Eip2930TransactionRequest, TransactionRequest, H160, H256, U256,
+};
+use ethers_signers::{Signer, Wallet};
+use proptest::prelude::*;
+use proptest::strategy::ValueTree;
+use proptest::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+use super::tx::{do_rlp, intrinsic_gas};
+
+/// Default number of the fuzz cases, the nightly run sets FUZZ_CASES
+const CASES: u32 = 16;
+
+/// Gas of the access list, EIP-2930
+const ACCESS_LIST_ADDRESS_GAS: u64 = 2400;
+const ACCESS_LIST_KEY_GAS: u64 = 1900;
+
+/// Budget of the fuzzer, set by the env variables:
+///  - FUZZ_CASES: number of the cases,
+///  - FUZZ_DURATION: time limit in seconds,
+///  - FUZZ_SEED: seed of the generator, the failed run is reproduced with the seed printed at the start
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct FuzzBudget {
+    pub cases: u32,
+    pub duration: Option<Duration>,
+    pub seed: u64,
+}
+
+#[allow(dead_code)]
+impl FuzzBudget {
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            env::var(name)
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<u64>().unwrap_or_else(|e| panic!("invalid {}: {}", name, e)))
+        };
+
+        let budget = Self {
+            cases: var("FUZZ_CASES").map(|x| x as u32).unwrap_or(CASES),
+            duration: var("FUZZ_DURATION").map(Duration::from_secs),
+            seed: var("FUZZ_SEED").unwrap_or_else(|| rand_core::RngCore::next_u64(&mut rand_core::OsRng)),
+        };
+        println!("[ Fuzz ] - budget {:?}", budget);
+        budget
+    }
+
+    /// Deterministic runner of the seed
+    pub fn runner(&self) -> TestRunner {
+        let mut seed = [0u8; 32];
+        seed[..8].copy_from_slice(&self.seed.to_le_bytes());
+        let config = Config { cases: self.cases, ..Config::default() };
+
+        TestRunner::new_with_rng(config, TestRng::from_seed(RngAlgorithm::ChaCha, &seed))
+    }
+
+    pub fn exhausted(&self, case: u32, start: Instant) -> bool {
+        case >= self.cases || self.duration.map(|d| start.elapsed() > d).unwrap_or(false)
+    }
+}
+
+/// Case of the fuzzer, prints the seed and the case if the case is not passed: the test panicked or returned an error.
+/// The line is kept in the records of the run, the case is reproduced with FUZZ_SEED and FUZZ_CASES above the case
+#[allow(dead_code)]
+pub struct FuzzCase<'a> {
+    pub budget: &'a FuzzBudget,
+    pub case: u32,
+    pub description: String,
+    passed: bool,
+}
+
+#[allow(dead_code)]
+impl<'a> FuzzCase<'a> {
+    pub fn new(budget: &'a FuzzBudget, case: u32, description: String) -> Self {
+        println!("[ Fuzz ] - case {}: {}", case, description);
+        Self { budget, case, description, passed: false }
+    }
+
+    pub fn pass(mut self) {
+        self.passed = true;
+    }
+}
+
+impl Drop for FuzzCase<'_> {
+    fn drop(&mut self) {
+        if !self.passed {
+            println!(
+                "[ Fuzz ] - failed: FUZZ_SEED={} case {}: {}",
+                self.budget.seed, self.case, self.description
+            );
+        }
+    }
+}
+
+/// Generate the value of the strategy
+#[allow(dead_code)]
+pub fn generate<S: Strategy>(strategy: &S, runner: &mut TestRunner) -> S::Value {
+    strategy.new_tree(runner).unwrap().current()
+}
+
+/// Mutation of the signed transaction
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RlpMutation {
+    /// the last byte is cut off
+    Truncated,
+    /// an extra byte is appended
+    TrailingBytes,
+    /// the byte of s is flipped, the signature recovers another sender or is invalid
+    BadSignature(u8),
+    /// the type byte is replaced with an unknown one
+    UnknownType,
+}
+
+impl RlpMutation {
+    fn apply(&self, rlp: &mut Vec<u8>) {
+        match self {
+            RlpMutation::Truncated => {
+                rlp.pop();
+            },
+            RlpMutation::TrailingBytes => rlp.push(0),
+            RlpMutation::BadSignature(i) => {
+                // s is the last field of all the transaction types
+                let index = rlp.len() - 1 - (*i as usize % 32);
+                rlp[index] ^= 0xff;
+            },
+            RlpMutation::UnknownType => rlp.insert(0, 0x7f),
+        }
+    }
+}
+
+/// Random transfer to a new account
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct FuzzTx {
+    /// 0: legacy, 1: eip-2930, 2: eip-1559
+    pub tx_type: u8,
+    pub data: Vec<u8>,
+    /// value in percents of the balance left after the gas fee, above 100 is unaffordable
+    pub value_share: u8,
+    /// gas limit, the intrinsic gas if not set
+    pub gas: Option<u64>,
+    /// not used by the legacy transaction
+    pub access_list: AccessList,
+    pub mutation: Option<RlpMutation>,
+}
+
+#[allow(dead_code)]
+impl FuzzTx {
+    pub fn intrinsic_gas(&self) -> u64 {
+        let access_list = if self.tx_type == 0 {
+            0
+        } else {
+            self.access_list
+                .0
+                .iter()
+                .map(|item| ACCESS_LIST_ADDRESS_GAS + ACCESS_LIST_KEY_GAS * item.storage_keys.len() as u64)
+                .sum()
+        };
+
+        intrinsic_gas(&self.data) + access_list
+    }
+
+    /// Signable transaction of the sender with the balance
+    pub fn build(
+        &self,
+        wallet: &Wallet<SigningKey>,
+        to: H160,
+        nonce: U256,
+        chain_id: u64,
+        gas_price: U256,
+        balance: U256,
+    ) -> TypedTransaction {
+        let gas = U256::from(self.gas.unwrap_or_else(|| self.intrinsic_gas()));
+        let value = balance.saturating_sub(gas * gas_price) / 100 * U256::from(self.value_share);
+
+        let legacy = TransactionRequest::new()
+            .from(wallet.address())
+            .to(to)
+            .data(self.data.clone())
+            .value(value)
+            .gas(gas)
+            .gas_price(gas_price)
+            .nonce(nonce)
+            .chain_id(chain_id);
+
+        match self.tx_type {
+            0 => legacy.into(),
+            1 => Eip2930TransactionRequest::new(legacy, self.access_list.clone()).into(),
+            2 => Eip1559TransactionRequest::new()
+                .from(wallet.address())
+                .to(to)
+                .data(self.data.clone())
+                .value(value)
+                .gas(gas)
+                .max_fee_per_gas(gas_price)
+                .max_priority_fee_per_gas(gas_price)
+                .nonce(nonce)
+                .chain_id(chain_id)
+                .access_list(self.access_list.clone())
+                .into(),
+            _ => unimplemented!("Transaction type not supported"),
+        }
+    }
+
+    /// Sign the transaction by [do_rlp] and apply the mutation
+    pub fn rlp(&self, tx: &TypedTransaction, wallet: &Wallet<SigningKey>) -> Vec<u8> {
+        let mut rlp = do_rlp(tx, wallet);
+        if let Some(mutation) = &self.mutation {
+            mutation.apply(&mut rlp);
+        }
+        rlp
+    }
+}
+
+fn rlp_mutation() -> impl Strategy<Value = RlpMutation> {
+    prop_oneof![
+        Just(RlpMutation::Truncated),
+        Just(RlpMutation::TrailingBytes),
+        any::<u8>().prop_map(RlpMutation::BadSignature),
+        Just(RlpMutation::UnknownType),
+    ]
+}
+
+fn access_list() -> impl Strategy<Value = AccessList> {
+    let item = (any::<[u8; 20]>(), prop::collection::vec(any::<[u8; 32]>(), 0..3)).prop_map(|(address, keys)| {
+        AccessListItem {
+            address: H160::from(address),
+            storage_keys: keys.into_iter().map(H256::from).collect(),
+        }
+    });
+
+    prop::collection::vec(item, 0..3).prop_map(AccessList)
+}
+
+/// Well-formed transactions, the gas below the intrinsic gas, unaffordable values
+/// and malformed RLP are generated as well
+#[allow(dead_code)]
+pub fn fuzz_tx() -> impl Strategy<Value = FuzzTx> {
+    (
+        0u8..3,
+        prop::collection::vec(any::<u8>(), 0..256),
+        0u8..=120,
+        prop::option::weighted(0.3, 0u64..100_000),
+        access_list(),
+        prop::option::weighted(0.2, rlp_mutation()),
+    )
+        .prop_map(|(tx_type, data, value_share, gas, access_list, mutation)| FuzzTx {
+            tx_type,
+            data,
+            value_share,
+            gas,
+            access_list,
+            mutation,
+        })
+}
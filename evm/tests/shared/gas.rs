@@ -0,0 +1,263 @@
+use std::{env, fs::OpenOptions, io::Write, path::Path};
+use ethereum_abi::{Abi, Type, Value};
+use ethers::types::TransactionRequest;
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{Address, NameOrAddress, U256};
+use ethers_signers::{Signer, Wallet};
+use super::{client::Client, tx::do_tx_with_gas};
+
+/// Default bounds of estimate / actual gas ratio
+const RATIO_MIN: f64 = 1.0;
+const RATIO_MAX: f64 = 2.0;
+
+/// Accepted range of the estimate / actual gas ratio.
+/// A ratio below the minimum is an underestimate, above the maximum the user overpays.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct GasBand {
+    pub min: f64,
+    pub max: f64,
+}
+
+#[allow(dead_code)]
+impl GasBand {
+    /// The bounds are overridden by GAS_RATIO_MIN and GAS_RATIO_MAX env variables
+    pub fn from_env() -> Self {
+        let bound = |name: &str, default: f64| {
+            env::var(name)
+                .map(|s| s.parse().unwrap_or_else(|e| panic!("invalid {}: {}", name, e)))
+                .unwrap_or(default)
+        };
+
+        Self {
+            min: bound("GAS_RATIO_MIN", RATIO_MIN),
+            max: bound("GAS_RATIO_MAX", RATIO_MAX),
+        }
+    }
+
+    pub fn contains(&self, ratio: f64) -> bool {
+        self.min <= ratio && ratio <= self.max
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum GasStatus {
+    /// the ratio is inside the band
+    Ok,
+    /// the ratio is outside the band
+    OutOfBand,
+    /// the transaction failed with the estimated gas limit
+    Failed(String),
+    /// estimate_gas failed, the method reverts with the default arguments
+    Skipped(String),
+}
+
+/// Estimate and actual gas of one contract method
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct GasRecord {
+    pub contract: String,
+    pub method: String,
+    pub estimate: Option<U256>,
+    pub actual: Option<U256>,
+    pub status: GasStatus,
+}
+
+#[allow(dead_code)]
+impl GasRecord {
+    pub fn ratio(&self) -> Option<f64> {
+        match (self.estimate, self.actual) {
+            (Some(estimate), Some(actual)) if !actual.is_zero() => {
+                Some(estimate.as_u128() as f64 / actual.as_u128() as f64)
+            },
+            _ => None,
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self.status, GasStatus::OutOfBand | GasStatus::Failed(_))
+    }
+
+    fn status_str(&self) -> (&str, String) {
+        match &self.status {
+            GasStatus::Ok => ("ok", String::new()),
+            GasStatus::OutOfBand => ("out_of_band", String::new()),
+            GasStatus::Failed(e) => ("failed", e.clone()),
+            GasStatus::Skipped(e) => ("skipped", e.clone()),
+        }
+    }
+}
+
+/// Gas accuracy table of the contract methods
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct GasTable {
+    pub records: Vec<GasRecord>,
+}
+
+#[allow(dead_code)]
+impl GasTable {
+    pub fn push(&mut self, record: GasRecord) {
+        println!("[ Gas ] - {}.{}: estimate {:?}, actual {:?}, ratio {:?}, {:?}",
+            record.contract, record.method, record.estimate, record.actual, record.ratio(), record.status
+        );
+        self.records.push(record);
+    }
+
+    pub fn failures(&self) -> Vec<&GasRecord> {
+        self.records.iter().filter(|r| r.is_failure()).collect()
+    }
+
+    pub fn csv(&self) -> String {
+        let mut csv = "contract,method,estimate,actual,ratio,status,error\n".to_string();
+        for record in &self.records {
+            let (status, error) = record.status_str();
+            csv += &format!("{},{},{},{},{},{},\"{}\"\n",
+                record.contract,
+                record.method,
+                record.estimate.map(|x| x.to_string()).unwrap_or_default(),
+                record.actual.map(|x| x.to_string()).unwrap_or_default(),
+                record.ratio().map(|x| format!("{:.3}", x)).unwrap_or_default(),
+                status,
+                error.replace('"', "'"),
+            );
+        }
+        csv
+    }
+
+    pub fn json(&self) -> serde_json::Value {
+        let records = self.records.iter().map(|record| {
+            let (status, error) = record.status_str();
+            serde_json::json!({
+                "contract": record.contract,
+                "method": record.method,
+                "estimate": record.estimate.map(|x| x.to_string()),
+                "actual": record.actual.map(|x| x.to_string()),
+                "ratio": record.ratio(),
+                "status": status,
+                "error": error,
+            })
+        });
+        serde_json::Value::Array(records.collect())
+    }
+
+    /// The table is printed as CSV and appended to the file set by the GAS_REPORT env variable,
+    /// one table per contract. The format is chosen by the extension: .json (one record per line) or .csv
+    pub fn write(&self) {
+        let csv = self.csv();
+        println!("{}", csv);
+
+        if let Ok(path) = env::var("GAS_REPORT") {
+            let content = match Path::new(&path).extension().and_then(|e| e.to_str()) {
+                Some("json") => self
+                    .json()
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|record| format!("{}\n", record))
+                    .collect(),
+                // the header is written once
+                _ if Path::new(&path).exists() => csv.lines().skip(1).map(|line| format!("{}\n", line)).collect(),
+                _ => csv,
+            };
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .unwrap_or_else(|e| panic!("failed to open {}: {}", path, e));
+            file.write_all(content.as_bytes()).unwrap();
+        }
+    }
+}
+
+/// Calldata of the abi functions with the default arguments.
+/// The functions with unsupported argument types are not included
+#[allow(dead_code)]
+pub fn abi_calls(abi: &Abi) -> Vec<(String, Vec<u8>)> {
+    abi.functions
+        .iter()
+        .filter_map(|function| {
+            let args = function
+                .inputs
+                .iter()
+                .map(|param| match &param.type_ {
+                    Type::Uint(size) => Some(Value::Uint(primitive_types::U256::one(), *size)),
+                    Type::Bool => Some(Value::Bool(false)),
+                    Type::String => Some(Value::String("rome".to_string())),
+                    Type::FixedBytes(size) => Some(Value::FixedBytes(vec![1; *size])),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let mut data = function.method_id().to_vec();
+            data.append(&mut Value::encode(&args));
+            Some((function.name.clone(), data))
+        })
+        .collect()
+}
+
+/// Estimate the gas of the call, send it with the estimated gas limit
+/// and compare the estimate with the gas charged from the sender
+#[allow(dead_code)]
+pub async fn measure(
+    client: &Client,
+    contract: &str,
+    address: Address,
+    method: &str,
+    data: Vec<u8>,
+    wallet: &Wallet<SigningKey>,
+    band: &GasBand,
+) -> GasRecord {
+    let mut record = GasRecord {
+        contract: contract.to_string(),
+        method: method.to_string(),
+        estimate: None,
+        actual: None,
+        status: GasStatus::Ok,
+    };
+
+    let request = TransactionRequest {
+        from: Some(wallet.address()),
+        to: Some(NameOrAddress::Address(address)),
+        data: Some(data.clone().into()),
+        gas_price: Some(client.gas_price().unwrap()),
+        chain_id: Some(client.chain_id().into()),
+        ..Default::default()
+    };
+    let estimate = match client.estimate_gas(&request) {
+        Ok(estimate) => estimate,
+        Err(e) => {
+            record.status = GasStatus::Skipped(format!("{:?}", e));
+            return record;
+        },
+    };
+    record.estimate = Some(estimate);
+
+    // the gas used is found by the price of the signed transaction
+    let tx = do_tx_with_gas(client, Some(address), data, wallet, 0.into(), 0, estimate);
+    let gas_price = tx.gas_price().unwrap_or_default();
+    if gas_price.is_zero() {
+        record.status = GasStatus::Skipped("fee-free rollup, the gas used is not charged".to_string());
+        return record;
+    }
+
+    let before = client.get_balance(wallet.address()).unwrap();
+    if let Err(e) = client.try_send_tx(&tx, wallet).await {
+        record.status = GasStatus::Failed(format!("{:?}", e));
+        return record;
+    }
+    let after = client.get_balance(wallet.address()).unwrap();
+
+    let (actual, rest) = before.checked_sub(after).unwrap().div_mod(gas_price);
+    if !rest.is_zero() {
+        record.status = GasStatus::Failed(format!("fee {} is not a multiple of the gas price {}", before - after, gas_price));
+        return record;
+    }
+    record.actual = Some(actual);
+    if !record.ratio().map(|ratio| band.contains(ratio)).unwrap_or(false) {
+        record.status = GasStatus::OutOfBand;
+    }
+
+    record
+}
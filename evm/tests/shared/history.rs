@@ -0,0 +1,140 @@
+use ethers::providers::{Http, Middleware, Provider};
+use ethers_core::types::{BlockId, BlockNumber, Bytes, TransactionReceipt, H160, H256, U256, U64};
+
+/// State of the account at the block
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountState {
+    pub nonce: U256,
+    pub balance: U256,
+    pub code: Bytes,
+    /// values of the recorded slots
+    pub storage: Vec<H256>,
+}
+
+/// Query the account state at the block
+#[allow(dead_code)]
+pub async fn account_state(
+    provider: &Provider<Http>,
+    address: H160,
+    slots: &[u64],
+    block: BlockId,
+) -> Result<AccountState, Box<dyn std::error::Error>> {
+    let mut storage = vec![];
+    for slot in slots {
+        storage.push(provider.get_storage_at(address, H256::from_low_u64_be(*slot), Some(block)).await?);
+    }
+
+    Ok(AccountState {
+        nonce: provider.get_transaction_count(address, Some(block)).await?,
+        balance: provider.get_balance(address, Some(block)).await?,
+        code: provider.get_code(address, Some(block)).await?,
+        storage,
+    })
+}
+
+/// State of the account after the transaction
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Checkpoint {
+    pub number: U64,
+    pub hash: H256,
+    pub state: AccountState,
+}
+
+/// States of the account expected during the scenario, used as the expectations of the historical queries.
+/// The states are derived from the calls of the scenario, not queried from the providers
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct History {
+    pub address: H160,
+    pub slots: Vec<u64>,
+    /// expected state after the last recorded transaction
+    pub state: AccountState,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+#[allow(dead_code)]
+impl History {
+    /// State of the contract after the deployment: the nonce is 1 (EIP-161), the balance and the slots are zero.
+    /// The code is not changed by the scenario
+    pub fn new(address: H160, slots: &[u64], code: Bytes) -> Self {
+        let state = AccountState {
+            nonce: U256::one(),
+            balance: U256::zero(),
+            code,
+            storage: vec![H256::zero(); slots.len()],
+        };
+        Self { address, slots: slots.to_vec(), state, checkpoints: vec![] }
+    }
+
+    /// Apply the change made by the transaction to the expected state
+    /// and record it as the state at the block of the transaction
+    pub fn record(&mut self, recipt: &TransactionReceipt, change: impl FnOnce(&mut AccountState)) {
+        change(&mut self.state);
+        let checkpoint = Checkpoint {
+            number: recipt.block_number.unwrap(),
+            hash: recipt.block_hash.unwrap(),
+            state: self.state.clone(),
+        };
+        println!("[ History ] - block {}: {:?}", checkpoint.number, checkpoint.state);
+        self.checkpoints.push(checkpoint);
+    }
+
+    /// Expected state at the block: the last checkpoint at or before the block,
+    /// the empty account before the first one
+    pub fn expected(&self, number: U64) -> AccountState {
+        self.checkpoints
+            .iter()
+            .rev()
+            .find(|checkpoint| checkpoint.number <= number)
+            .map(|checkpoint| checkpoint.state.clone())
+            .unwrap_or_default()
+    }
+
+    /// Query the account at the block tags, numbers and hashes of the checkpoints.
+    /// Returns the differences from the recorded states
+    pub async fn check(&self, provider: &Provider<Http>, provider_name: &str) -> Vec<String> {
+        let first = self.checkpoints.first().expect("no checkpoints").number;
+        let last = self.checkpoints.last().unwrap().number;
+        let mut queries: Vec<(String, BlockId, AccountState)> = vec![
+            ("earliest".to_string(), BlockNumber::Earliest.into(), AccountState::default()),
+            (format!("before {}", first), BlockNumber::Number(first - 1).into(), self.expected(first - 1)),
+            ("latest".to_string(), BlockNumber::Latest.into(), self.expected(last)),
+            ("pending".to_string(), BlockNumber::Pending.into(), self.expected(last)),
+        ];
+
+        for checkpoint in &self.checkpoints {
+            queries.push((format!("number {}", checkpoint.number), checkpoint.number.into(), checkpoint.state.clone()));
+            // EIP-1898: {"blockHash": ...}
+            queries.push((format!("hash {:?}", checkpoint.hash), checkpoint.hash.into(), checkpoint.state.clone()));
+        }
+
+        // safe and finalized blocks lag behind the latest one, the state is expected at their number
+        let latest = provider.get_block_number().await.unwrap();
+        for tag in [BlockNumber::Safe, BlockNumber::Finalized] {
+            match provider.get_block(tag).await {
+                Ok(Some(block)) => {
+                    let number = block.number.unwrap();
+                    assert!(number <= latest, "{} block {} is after the latest {}", tag, number, latest);
+                    queries.push((format!("{} ({})", tag, number), tag.into(), self.expected(number)));
+                },
+                result => println!("[ History ] - {}: {} block is not available: {:?}", provider_name, tag, result),
+            }
+        }
+
+        let mut diffs = vec![];
+        for (name, block, expected) in queries {
+            match account_state(provider, self.address, &self.slots, block).await {
+                Ok(state) if state == expected => println!("[ History ] - {} {}: ok", provider_name, name),
+                Ok(state) => diffs.push(format!("{} {}: expected {:?}, actual {:?}", provider_name, name, expected, state)),
+                Err(e) => diffs.push(format!("{} {}: {}", provider_name, name, e)),
+            }
+        }
+
+        for diff in &diffs {
+            println!("[ History ] - differs {}", diff);
+        }
+        diffs
+    }
+}
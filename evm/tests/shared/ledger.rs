@@ -0,0 +1,150 @@
+use std::{collections::BTreeMap, error::Error, fs};
+use ethers::providers::{Http, Middleware, Provider};
+use ethers_core::types::{Address, U256};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use super::client::Client;
+
+/// Number of wei in one lamport
+#[allow(dead_code)]
+pub const WEI_PER_LAMPORT: u64 = 1_000_000_000;
+
+/// Balances of rollup and Solana accounts at one step of a scenario
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default)]
+pub struct Ledger {
+    /// rollup balances in wei
+    pub evm: BTreeMap<Address, U256>,
+    /// solana balances in lamports
+    pub solana: BTreeMap<Pubkey, u64>,
+}
+
+#[allow(dead_code)]
+impl Ledger {
+    /// Load the balances of the accounts
+    pub async fn take(client: &Client, evm: &[Address], solana: &[Pubkey]) -> Result<Self, Box<dyn Error>> {
+        let mut ledger = Self::default();
+
+        for address in evm {
+            let balance = client.get_balance(*address).map_err(|e| format!("{:?}", e))?;
+            ledger.evm.insert(*address, balance);
+        }
+        for key in solana {
+            let balance = client.solana_rpc.get_balance(key).await?;
+            ledger.solana.insert(*key, balance);
+        }
+
+        Ok(ledger)
+    }
+
+    /// Rollup balance in wei
+    pub fn balance(&self, address: &Address) -> U256 {
+        *self.evm.get(address).expect("address is not in the ledger")
+    }
+
+    /// Solana balance in lamports
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        *self.solana.get(key).expect("solana key is not in the ledger")
+    }
+
+    /// Sum of the rollup balances in wei
+    pub fn evm_sum(&self, addresses: &[Address]) -> U256 {
+        addresses
+            .iter()
+            .fold(U256::zero(), |sum, address| sum + self.balance(address))
+    }
+
+    /// Sum of the solana balances converted to wei
+    pub fn solana_sum(&self, keys: &[Pubkey]) -> U256 {
+        keys
+            .iter()
+            .fold(U256::zero(), |sum, key| sum + to_wei(self.lamports(key)))
+    }
+}
+
+/// Balance of the operator on Solana and of its fee recipient on the rollup.
+/// The uniswap scripts take it before and after the run, see [OperatorBalances::report]
+#[allow(dead_code)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct OperatorBalances {
+    pub operator: String,
+    pub fee_recipient: Address,
+    /// lamports
+    pub operator_balance: u64,
+    /// wei
+    pub fee_recipient_balance: U256,
+}
+
+#[allow(dead_code)]
+impl OperatorBalances {
+    pub async fn take(
+        solana: &RpcClient,
+        provider: &Provider<Http>,
+        operator: &Pubkey,
+        fee_recipient: Address,
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            operator: operator.to_string(),
+            fee_recipient,
+            operator_balance: solana.get_balance(operator).await?,
+            fee_recipient_balance: provider.get_balance(fee_recipient, None).await?,
+        })
+    }
+
+    pub fn save(&self, path: &str) {
+        fs::write(path, serde_json::to_string_pretty(self).unwrap())
+            .unwrap_or_else(|e| panic!("failed to write {}: {}", path, e));
+    }
+
+    pub fn load(path: &str) -> Self {
+        let content = fs::read_to_string(path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e));
+        serde_json::from_str(&content).unwrap()
+    }
+
+    /// Print the balance changes and check that the lamports spent by the operator
+    /// are received by the fee recipient. The changes are compared in wei, without rounding
+    pub fn report(&self, after: &Self) -> bool {
+        let spent = delta(to_wei(after.operator_balance), to_wei(self.operator_balance));
+        let received = delta(self.fee_recipient_balance, after.fee_recipient_balance);
+        let lamports = |wei: i128| wei / WEI_PER_LAMPORT as i128;
+
+        println!("========================= ECONOMIC TESTS =========================");
+        println!("[ Info ] - Balances in lamports");
+        println!("[ Info ] - OPERATOR_BAL_BEFORE_: {}", self.operator_balance);
+        println!("[ Info ] - OPERATOR_BAL_AFTER__: {}", after.operator_balance);
+        println!("[ Info ] - FEE_RECIPIENT_BEFORE: {}", to_lamports(self.fee_recipient_balance));
+        println!("[ Info ] - FEE_RECIPIENT_AFTER_: {}", to_lamports(after.fee_recipient_balance));
+        println!("[ Info ] - {} < OPERATOR | OPERATOR_BAL_BEFORE - OPERATOR_BAL_AFTER", lamports(spent));
+        println!("[ Info ] - {} < FEE_RECIPIENT | FEE_RECIPIENT_BAL_AFTER - FEE_RECIPIENT_BAL_BEFORE", lamports(received));
+
+        let equal = spent == received;
+        if equal {
+            println!("[ Info ] - Balances are equal");
+        } else {
+            println!("[ Warning ] - Balances are different! wei: operator {}, fee recipient {}", spent, received);
+        }
+        println!("========================= -------------- =========================");
+        equal
+    }
+}
+
+/// Signed change of the balance in wei
+#[allow(dead_code)]
+fn delta(before: U256, after: U256) -> i128 {
+    if after >= before {
+        (after - before).as_u128() as i128
+    } else {
+        -((before - after).as_u128() as i128)
+    }
+}
+
+#[allow(dead_code)]
+pub fn to_wei(lamports: u64) -> U256 {
+    U256::from(lamports) * U256::from(WEI_PER_LAMPORT)
+}
+
+/// Wei to lamports, the remainder below 1 lamport is truncated
+#[allow(dead_code)]
+pub fn to_lamports(wei: U256) -> u64 {
+    (wei / U256::from(WEI_PER_LAMPORT)).as_u64()
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{env, fs};
+use ethers_core::types::{transaction::eip2718::TypedTransaction, Address, Bytes};
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_signers::{Signer, Wallet};
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use super::{
+    client::Client,
+    emulation::emulate_tx,
+    resources::load_report,
+    tx::{abi, do_rlp, do_tx, method_id},
+    CONTRACTS,
+};
+
+/// Time to wait for the first iteration of the locking transaction
+const START_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Outcome of the transaction sent while the accounts are locked
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// the transaction is executed while the accounts are locked
+    Ok,
+    /// rejected while the accounts are locked, the same transaction is executed after the lock is released
+    Retried,
+    /// rejected, the retry failed too
+    Failed(String),
+}
+
+#[allow(dead_code)]
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Ok => "ok",
+            Outcome::Retried => "retried",
+            Outcome::Failed(_) => "failed",
+        }
+    }
+}
+
+/// Outcomes of both transactions of the conflict
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    /// iterative transaction holding the lock
+    pub first: Outcome,
+    /// transaction sent between the iterations of the first one
+    pub second: Outcome,
+    /// accounts of the second transaction held by the first one: account -> written by any of them
+    pub shared: BTreeMap<Pubkey, bool>,
+}
+
+#[allow(dead_code)]
+impl Conflict {
+    /// Number of the shared accounts written by any of the transactions
+    pub fn written(&self) -> usize {
+        self.shared.values().filter(|written| **written).count()
+    }
+}
+
+/// The first transaction released the lock before the second one was executed or rejected,
+/// the scenario must be repeated
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct WindowMissed;
+
+/// Send the first (iterative) transaction, wait for its first iteration on Solana,
+/// then send the second transaction to the locked contract.
+/// Both transactions call the methods of the contract deployed at the address.
+///
+/// The overlap is checked by the slots of the solana transactions of the first one:
+/// the second transaction must be executed, or rejected, before the last iteration.
+#[allow(dead_code)]
+pub async fn run_conflict(
+    client: Arc<Client>,
+    contract: &str,
+    address: Address,
+    first: &str,
+    second: &str,
+    tx_type: u8,
+) -> Result<Conflict, WindowMissed> {
+    let wallet1 = super::wallet();
+    let wallet2 = super::wallet();
+    let tx_first = tx(&client, contract, address, first, &wallet1, tx_type);
+    let tx_second = tx(&client, contract, address, second, &wallet2, tx_type);
+    let rlp_first: Bytes = do_rlp(&tx_first, &wallet1).into();
+    let rlp_second: Bytes = do_rlp(&tx_second, &wallet2).into();
+
+    // accounts held by the first transaction until its last iteration
+    let held = emulate_tx(&client, &tx_first, &wallet1).await.accounts;
+    let shared = emulate_tx(&client, &tx_second, &wallet2)
+        .await
+        .accounts
+        .into_iter()
+        .filter_map(|(key, writable)| held.get(&key).map(|held| (key, writable || *held)))
+        .collect();
+
+    // the first iteration is found by the new signatures of the held accounts
+    let mut since = vec![];
+    for account in held.keys() {
+        since.push(latest_signature(&client, account).await);
+    }
+
+    let client1 = client.clone();
+    let first_jh = tokio::spawn(async move { client1.send_transaction(rlp_first).await });
+
+    let start = Instant::now();
+    'wait: loop {
+        for (account, since) in held.keys().zip(&since) {
+            if latest_signature(&client, account).await != *since {
+                break 'wait;
+            }
+        }
+        if first_jh.is_finished() {
+            return Err(WindowMissed);
+        }
+        assert!(start.elapsed() < START_TIMEOUT, "iterative transaction is not started");
+        async_std::task::sleep(POLL_INTERVAL).await;
+    }
+
+    let sent_slot = client.solana_rpc.get_slot().await.unwrap();
+    let second_result = client.send_transaction(rlp_second.clone()).await;
+    let rejected_slot = client.solana_rpc.get_slot().await.unwrap();
+
+    let (first, released) = match first_jh.await.unwrap() {
+        Ok(signatures) => {
+            let report = load_report(&client.solana_rpc, &client.program_id, &signatures).await;
+            (Outcome::Ok, report.solana_txs.last().map(|tx| tx.slot))
+        },
+        Err(e) => (Outcome::Failed(format!("{:?}", e)), None),
+    };
+    // the lock is released by the last iteration
+    let locked = |slot: u64| released.map_or(true, |released| slot < released);
+
+    let second = match second_result {
+        Ok(signatures) => {
+            let report = load_report(&client.solana_rpc, &client.program_id, &signatures).await;
+            let executed = report.solana_txs.first().map_or(sent_slot, |tx| tx.slot);
+            if !locked(executed) {
+                println!("second transaction executed at slot {}, the lock is released at {:?}", executed, released);
+                return Err(WindowMissed);
+            }
+            Outcome::Ok
+        },
+        Err(e) => {
+            if !locked(rejected_slot) {
+                println!("second transaction rejected at slot {}, the lock is released at {:?}", rejected_slot, released);
+                return Err(WindowMissed);
+            }
+            // the lock is released, the same transaction is sent again
+            match client.send_transaction(rlp_second).await {
+                Ok(_) => Outcome::Retried,
+                Err(retry) => Outcome::Failed(format!("{:?}, retry: {:?}", e, retry)),
+            }
+        },
+    };
+
+    Ok(Conflict { first, second, shared })
+}
+
+/// Row of the lock matrix: the methods of the transactions, the expected and the actual outcome
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct LockRow {
+    pub first: String,
+    pub second: String,
+    pub expected: Outcome,
+    /// None if the transactions did not overlap
+    pub conflict: Option<Conflict>,
+}
+
+#[allow(dead_code)]
+impl LockRow {
+    pub fn passed(&self) -> bool {
+        self.conflict
+            .as_ref()
+            .map_or(false, |c| c.first == Outcome::Ok && c.second == self.expected)
+    }
+}
+
+/// Outcomes of all the conflicts of the scenario
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default)]
+pub struct LockMatrix {
+    pub rows: Vec<LockRow>,
+}
+
+#[allow(dead_code)]
+impl LockMatrix {
+    pub fn csv(&self) -> String {
+        let mut csv = "first,second,expected,first_outcome,second_outcome,shared,written,status\n".to_string();
+        for row in &self.rows {
+            let (first, second, shared, written) = match &row.conflict {
+                Some(c) => (c.first.as_str(), c.second.as_str(), c.shared.len(), c.written()),
+                None => ("-", "window_missed", 0, 0),
+            };
+            csv += &format!(
+                "{},{},{},{},{},{},{},{}\n",
+                row.first,
+                row.second,
+                row.expected.as_str(),
+                first,
+                second,
+                shared,
+                written,
+                if row.passed() { "pass" } else { "fail" },
+            );
+        }
+        csv
+    }
+
+    /// Print the matrix, the file is written if the LOCK_MATRIX env variable is set
+    pub fn write(&self) {
+        for row in &self.rows {
+            println!("[ Lock ] - {} x {}: expected {:?}, actual {:?}", row.first, row.second, row.expected, row.conflict);
+        }
+        println!("{}", self.csv());
+
+        if let Ok(path) = env::var("LOCK_MATRIX") {
+            fs::write(&path, self.csv()).unwrap_or_else(|e| panic!("failed to write {}: {}", path, e));
+        }
+    }
+
+    pub fn failed(&self) -> Vec<&LockRow> {
+        self.rows.iter().filter(|row| !row.passed()).collect()
+    }
+}
+
+fn tx(
+    client: &Client,
+    contract: &str,
+    address: Address,
+    method: &str,
+    wallet: &Wallet<SigningKey>,
+    tx_type: u8,
+) -> TypedTransaction {
+    let abi = abi(&format!("{}{}.abi", CONTRACTS, contract));
+    println!("{} from {:?}", method, wallet.address());
+    do_tx(client, Some(address), method_id(&abi, method), wallet, 0.into(), tx_type)
+}
+
+async fn latest_signature(client: &Client, account: &Pubkey) -> Option<String> {
+    let config = GetConfirmedSignaturesForAddress2Config {
+        limit: Some(1),
+        commitment: Some(CommitmentConfig::confirmed()),
+        ..Default::default()
+    };
+    client
+        .solana_rpc
+        .get_signatures_for_address_with_config(account, config)
+        .await
+        .unwrap()
+        .first()
+        .map(|status| status.signature.clone())
+}
@@ -0,0 +1,174 @@
+use std::{collections::BTreeMap, time::{Duration, Instant}};
+use ethers::providers::{Http, Middleware, PendingTransaction, Provider};
+use ethers::utils::keccak256;
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{
+    transaction::eip2718::TypedTransaction, BlockNumber, Bytes, Eip1559TransactionRequest, TransactionRequest, H256,
+    U256,
+};
+use ethers_signers::{Signer, Wallet};
+use super::utils::{
+    airdrop_to_address, get_chain_id, get_random_wallet, return_current_provider, wait_for_confirmation,
+    wait_for_inclusion,
+};
+
+/// Time to wait for the inclusion of the transactions
+const INCLUSION_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Mempool scenario of one sender on one provider.
+/// Every step appends the observation to the log, the logs of proxy and geth are compared.
+/// The nonces are relative to the nonce of the sender at the start of the scenario
+#[allow(dead_code)]
+pub struct Mempool {
+    pub provider_name: String,
+    pub provider: Provider<Http>,
+    pub wallet: Wallet<SigningKey>,
+    /// gas price of the provider at the start of the scenario
+    pub gas_price: U256,
+    pub base_nonce: U256,
+    /// hashes of the signed transactions by name
+    pub hashes: BTreeMap<String, H256>,
+    pub log: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl Mempool {
+    /// Fund a new sender on the provider
+    pub async fn new(provider_name: &str) -> Self {
+        let provider = return_current_provider(provider_name);
+        let wallet = get_random_wallet();
+        airdrop_to_address(wallet.address(), U256::exp10(18), provider_name).await.unwrap();
+
+        Self {
+            provider_name: provider_name.to_string(),
+            gas_price: provider.get_gas_price().await.unwrap(),
+            base_nonce: provider.get_transaction_count(wallet.address(), None).await.unwrap(),
+            provider,
+            wallet,
+            hashes: BTreeMap::new(),
+            log: vec![],
+        }
+    }
+
+    fn push(&mut self, observation: String) {
+        println!("[ Mempool ] - {}: {}", self.provider_name, observation);
+        self.log.push(observation);
+    }
+
+    /// Signed legacy transfer of 1 wei to the sender itself
+    pub fn transfer(&self, nonce: u64, gas_price: U256) -> Bytes {
+        let tx: TypedTransaction = TransactionRequest::new()
+            .from(self.wallet.address())
+            .to(self.wallet.address())
+            .value(1)
+            .gas(21_000)
+            .gas_price(gas_price)
+            .nonce(self.base_nonce + nonce)
+            .chain_id(get_chain_id())
+            .into();
+        let signature = self.wallet.sign_transaction_sync(&tx).unwrap();
+        tx.rlp_signed(&signature)
+    }
+
+    /// Signed eip-1559 transfer of 1 wei to the sender itself, the tip is capped by the fee cap
+    pub fn transfer_1559(&self, nonce: u64, max_fee_per_gas: U256) -> Bytes {
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .from(self.wallet.address())
+            .to(self.wallet.address())
+            .value(1)
+            .gas(21_000)
+            .max_fee_per_gas(max_fee_per_gas)
+            .max_priority_fee_per_gas(max_fee_per_gas.min(U256::one()))
+            .nonce(self.base_nonce + nonce)
+            .chain_id(get_chain_id())
+            .into();
+        let signature = self.wallet.sign_transaction_sync(&tx).unwrap();
+        tx.rlp_signed(&signature)
+    }
+
+    /// Base fee of the latest block
+    pub async fn base_fee(&self) -> U256 {
+        let block = self.provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+        block.base_fee_per_gas.unwrap_or_default()
+    }
+
+    /// Send the raw transaction, only the acceptance is logged, the error messages differ between the providers
+    pub async fn send(&mut self, name: &str, raw: Bytes) -> bool {
+        self.hashes.insert(name.to_string(), H256::from(keccak256(&raw)));
+        let result = self.provider.send_raw_transaction(raw).await;
+        if let Err(e) = &result {
+            println!("[ Mempool ] - {}: {} rejected: {}", self.provider_name, name, e);
+        }
+        self.push(format!("send {}: {}", name, if result.is_ok() { "accepted" } else { "rejected" }));
+        result.is_ok()
+    }
+
+    /// eth_getTransactionByHash of the transaction not included yet
+    pub async fn visible(&mut self, name: &str) -> bool {
+        let tx = self.provider.get_transaction(self.hashes[name]).await.unwrap();
+        let visible = tx.is_some();
+        self.push(format!("visible {}: {}", name, visible));
+        visible
+    }
+
+    /// Nonce of the sender at the pending block
+    pub async fn pending_nonce(&mut self) -> u64 {
+        let nonce = self
+            .provider
+            .get_transaction_count(self.wallet.address(), Some(BlockNumber::Pending.into()))
+            .await
+            .unwrap();
+        let nonce = (nonce - self.base_nonce).as_u64();
+        self.push(format!("pending nonce: +{}", nonce));
+        nonce
+    }
+
+    /// Wait until the latest nonce of the sender reaches the given one
+    pub async fn settle(&mut self, nonce: u64) -> u64 {
+        let start = Instant::now();
+        let latest = loop {
+            let latest = self.provider.get_transaction_count(self.wallet.address(), None).await.unwrap();
+            let latest = (latest - self.base_nonce).as_u64();
+            if latest >= nonce || start.elapsed() > INCLUSION_TIMEOUT {
+                break latest;
+            }
+            async_std::task::sleep(POLL_INTERVAL).await;
+        };
+        self.push(format!("latest nonce: +{}", latest));
+        latest
+    }
+
+    /// Log which transactions are included and the order of the inclusion
+    pub async fn included(&mut self, names: &[&str]) -> Vec<String> {
+        let mut included = vec![];
+        for name in names {
+            let receipt = self.provider.get_transaction_receipt(self.hashes[*name]).await.unwrap();
+            self.push(format!("included {}: {}", name, receipt.is_some()));
+            if let Some(receipt) = receipt {
+                included.push((receipt.block_number.unwrap(), receipt.transaction_index, name.to_string()));
+            }
+        }
+
+        included.sort();
+        let order: Vec<String> = included.into_iter().map(|(_, _, name)| name).collect();
+        self.push(format!("order: {}", order.join(", ")));
+        order
+    }
+
+    /// Wait for the transaction as [airdrop_to_address] does, see [wait_for_confirmation]
+    pub async fn confirm(&mut self, name: &str) -> Result<(), String> {
+        let pending = PendingTransaction::new(self.hashes[name], &self.provider).interval(POLL_INTERVAL);
+        let result = wait_for_confirmation(pending).await.map_err(|e| e.to_string());
+        self.push(format!("confirm {}: {:?}", name, result));
+        result
+    }
+
+    /// Wait for the receipt of the transaction, see [wait_for_inclusion]
+    pub async fn wait(&mut self, name: &str) -> Result<(), String> {
+        let pending = PendingTransaction::new(self.hashes[name], &self.provider).interval(POLL_INTERVAL);
+        let result = wait_for_inclusion(pending).await.map(|_| ()).map_err(|e| e.to_string());
+        self.push(format!("wait {}: {:?}", name, result));
+        result
+    }
+}
@@ -8,11 +8,30 @@ use utils::{run_on_devnet, run_on_testnet};
 use solana_program::pubkey::Pubkey;
 use solana_sdk::signer::Signer;
 
+pub mod bytecode;
 pub mod client;
 pub mod config;
+pub mod differential;
+pub mod emulation;
+pub mod fees;
 pub mod fixture;
+pub mod fuzz;
+pub mod gas;
+pub mod history;
+pub mod ledger;
+pub mod lock;
+pub mod mempool;
+pub mod opcodes;
+pub mod overrides;
+pub mod parity;
+pub mod precompile;
+pub mod receipt;
+pub mod resources;
+pub mod retry;
+pub mod rpc;
 pub mod tx;
 pub mod utils;
+pub mod validation;
 
 pub const CONTRACTS: &'static str = "/opt/solidity/";
 pub const RHEA_CONFIG_PATH: &'static str = "/opt/ci/cfg/rhea-config.yml";
@@ -0,0 +1,150 @@
+use std::{env, fs, path::Path};
+use ethers::providers::Middleware;
+use ethers_core::types::{H160, H256};
+use serde_json::{json, Value};
+use super::utils::return_current_provider;
+
+/// Opcode groups of opcodes.sol: the contract and the names of its storage slots 0..n
+#[allow(dead_code)]
+pub const GROUPS: &[(&str, &[&str])] = &[
+    ("OpBlockContext", &[
+        "NUMBER", "TIMESTAMP", "BASEFEE", "PREVRANDAO", "CHAINID", "COINBASE",
+        "BLOCKHASH(number - 1)", "BLOCKHASH(number)", "BLOCKHASH(number - 257)", "GASLIMIT",
+    ]),
+    ("OpEnvironment", &[
+        "ORIGIN", "GASPRICE", "CALLER", "CALLVALUE", "EXTCODEHASH(self)", "EXTCODESIZE(self)",
+        "EXTCODECOPY(self)", "EXTCODEHASH(eoa)", "EXTCODESIZE(eoa)", "EXTCODEHASH(empty)", "BALANCE(self)",
+    ]),
+    ("OpMemory", &[
+        "MSIZE(1KB)", "GAS(1KB)", "MSIZE(32KB)", "GAS(32KB)", "MSIZE(64KB)", "GAS(64KB)",
+    ]),
+    ("OpReturnData", &[
+        "RETURNDATASIZE", "RETURNDATACOPY", "RETURNDATACOPY(out of bounds)", "RETURNDATACOPY(at end)",
+        "RETURNDATASIZE(failed call)",
+    ]),
+    ("OpPush0Mcopy", &[
+        "MCOPY", "MCOPY(overlap forward)", "MCOPY(overlap backward)", "MSIZE(mcopy)",
+    ]),
+];
+
+/// The difference from geth fails the test. The block context, the gas price
+/// and the remaining gas depend on the rollup, their differences are only reported
+#[allow(dead_code)]
+pub const MUST_MATCH: &[&str] = &[
+    "NUMBER", "CHAINID",
+    "ORIGIN", "CALLER", "CALLVALUE", "EXTCODEHASH(self)", "EXTCODESIZE(self)", "EXTCODECOPY(self)",
+    "EXTCODEHASH(eoa)", "EXTCODESIZE(eoa)", "EXTCODEHASH(empty)", "BALANCE(self)",
+    "MSIZE(1KB)", "MSIZE(32KB)", "MSIZE(64KB)",
+    "RETURNDATASIZE", "RETURNDATACOPY", "RETURNDATACOPY(out of bounds)", "RETURNDATACOPY(at end)",
+    "RETURNDATASIZE(failed call)",
+    "MCOPY", "MCOPY(overlap forward)", "MCOPY(overlap backward)", "MSIZE(mcopy)",
+];
+
+/// Storage slot written by the opcode group, read from proxy and geth
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct OpcodeRow {
+    pub contract: String,
+    pub name: String,
+    /// provider the transaction was sent through
+    pub provider: String,
+    pub proxy: H256,
+    pub geth: H256,
+    pub required: bool,
+}
+
+#[allow(dead_code)]
+impl OpcodeRow {
+    pub fn matches(&self) -> bool {
+        self.proxy == self.geth
+    }
+
+    fn status(&self) -> &'static str {
+        match (self.matches(), self.required) {
+            (true, _) => "pass",
+            (false, true) => "fail",
+            (false, false) => "differs",
+        }
+    }
+}
+
+/// Opcode semantics of the rollup compared with geth
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default)]
+pub struct OpcodeMatrix {
+    pub rows: Vec<OpcodeRow>,
+}
+
+#[allow(dead_code)]
+impl OpcodeMatrix {
+    /// Compare the named storage slots 0..n of the contract between proxy and geth
+    pub async fn compare(&mut self, contract: &str, provider_name: &str, address: H160, names: &[&str]) {
+        let proxy = return_current_provider("proxy");
+        let geth = return_current_provider("geth");
+
+        for (slot, name) in names.iter().enumerate() {
+            let slot = H256::from_low_u64_be(slot as u64);
+            let row = OpcodeRow {
+                contract: contract.to_string(),
+                name: name.to_string(),
+                provider: provider_name.to_string(),
+                proxy: proxy.get_storage_at(address, slot, None).await.unwrap(),
+                geth: geth.get_storage_at(address, slot, None).await.unwrap(),
+                required: MUST_MATCH.contains(name),
+            };
+            println!("[ Opcode ] - {} {}: {}, proxy {:?}, geth {:?}", provider_name, name, row.status(), row.proxy, row.geth);
+            self.rows.push(row);
+        }
+    }
+
+    /// Required opcodes that differ from geth
+    pub fn failures(&self) -> Vec<&OpcodeRow> {
+        self.rows.iter().filter(|row| row.required && !row.matches()).collect()
+    }
+
+    pub fn csv(&self) -> String {
+        let mut csv = "contract,opcode,provider,required,status,proxy,geth\n".to_string();
+        for row in &self.rows {
+            csv += &format!(
+                "{},\"{}\",{},{},{},{:?},{:?}\n",
+                row.contract,
+                row.name,
+                row.provider,
+                row.required,
+                row.status(),
+                row.proxy,
+                row.geth,
+            );
+        }
+        csv
+    }
+
+    pub fn json(&self) -> Value {
+        let rows = self.rows.iter().map(|row| {
+            json!({
+                "contract": row.contract,
+                "opcode": row.name,
+                "provider": row.provider,
+                "required": row.required,
+                "status": row.status(),
+                "proxy": row.proxy,
+                "geth": row.geth,
+            })
+        });
+        Value::Array(rows.collect())
+    }
+
+    /// The matrix is printed as CSV and written to the file set by the OPCODE_MATRIX env variable,
+    /// the format is chosen by the extension: .json or .csv
+    pub fn write(&self) {
+        println!("{}", self.csv());
+
+        if let Ok(path) = env::var("OPCODE_MATRIX") {
+            let content = match Path::new(&path).extension().and_then(|e| e.to_str()) {
+                Some("json") => serde_json::to_string_pretty(&self.json()).unwrap(),
+                _ => self.csv(),
+            };
+            fs::write(&path, content).unwrap_or_else(|e| panic!("failed to write {}: {}", path, e));
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use ethers_core::types::{Bytes, H160, H256, U256, U64};
+use serde::Serialize;
+
+/// Overridden fields of the account, the stateOverride entry of eth_call.
+/// `state` replaces the whole storage, `state_diff` replaces only the given slots
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<H256, H256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// The stateOverride parameter of eth_call
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct StateOverride(pub BTreeMap<H160, AccountOverride>);
+
+#[allow(dead_code)]
+impl StateOverride {
+    fn account(&mut self, address: H160) -> &mut AccountOverride {
+        self.0.entry(address).or_default()
+    }
+
+    pub fn balance(mut self, address: H160, balance: U256) -> Self {
+        self.account(address).balance = Some(balance);
+        self
+    }
+
+    pub fn nonce(mut self, address: H160, nonce: u64) -> Self {
+        self.account(address).nonce = Some(nonce.into());
+        self
+    }
+
+    pub fn code(mut self, address: H160, code: Bytes) -> Self {
+        self.account(address).code = Some(code);
+        self
+    }
+
+    pub fn state(mut self, address: H160, slot: H256, value: H256) -> Self {
+        self.account(address).state.get_or_insert_with(BTreeMap::new).insert(slot, value);
+        self
+    }
+
+    pub fn state_diff(mut self, address: H160, slot: H256, value: H256) -> Self {
+        self.account(address).state_diff.get_or_insert_with(BTreeMap::new).insert(slot, value);
+        self
+    }
+}
+
+/// The block overrides parameter of eth_call, the field names follow geth
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_recipient: Option<H160>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_randao: Option<H256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+}
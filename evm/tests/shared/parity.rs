@@ -0,0 +1,42 @@
+use ethers_core::types::{Address, U256};
+use super::client::Client;
+
+/// Balances of the sender and the fee recipients before the scenario,
+/// used to compare the fee-free and the fee-paying runs of the same test
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct ModeFees {
+    pub zero_gas: bool,
+    pub sender: Address,
+    pub balance: U256,
+    pub fees: U256,
+}
+
+#[allow(dead_code)]
+impl ModeFees {
+    pub async fn take(client: &Client, sender: Address, zero_gas: bool) -> Self {
+        Self {
+            zero_gas,
+            sender,
+            balance: client.get_balance(sender).unwrap(),
+            fees: client.sum_fee_balances(zero_gas).await,
+        }
+    }
+
+    /// Nothing is charged in the fee-free mode.
+    /// In the fee-paying mode the sender pays and the fee recipients receive the fee
+    pub async fn check(&self, client: &Client) {
+        let spent = self.balance.checked_sub(client.get_balance(self.sender).unwrap()).unwrap();
+        let received = client.sum_fee_balances(self.zero_gas).await.checked_sub(self.fees).unwrap();
+        println!("[ Parity ] - zero_gas {}: spent {}, received {}", self.zero_gas, spent, received);
+
+        if self.zero_gas {
+            assert_eq!(spent, U256::zero());
+            assert_eq!(received, U256::zero());
+        } else {
+            assert!(spent > U256::zero());
+            assert!(received > U256::zero());
+            assert!(received <= spent);
+        }
+    }
+}
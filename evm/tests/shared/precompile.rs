@@ -0,0 +1,274 @@
+use std::fs;
+use ethers_core::types::{Address, U256};
+use super::tx::intrinsic_gas;
+
+/// Precompile test vectors of go-ethereum (core/vm/testdata/precompiles), copied to the image by ci/Dockerfile
+#[allow(dead_code)]
+pub const PRECOMPILE_VECTORS: &'static str = "/opt/precompiles/";
+
+/// Gas of the identity precompile with the empty input, the baseline of the probe gas, see [probe_gas]
+#[allow(dead_code)]
+pub const IDENTITY_EMPTY_GAS: u64 = 15;
+
+/// Test vector of the precompile
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Vector {
+    pub name: String,
+    /// address of the precompile: 0x01..0x0a
+    pub precompile: u8,
+    pub input: Vec<u8>,
+    /// None if the precompile fails, the call consumes all the gas in this case
+    pub output: Option<Vec<u8>>,
+    /// gas charged by the precompile
+    pub gas: u64,
+}
+
+#[allow(dead_code)]
+impl Vector {
+    pub fn address(&self) -> Address {
+        Address::from_low_u64_be(self.precompile as u64)
+    }
+
+    /// Gas of the transaction calling the precompile directly
+    pub fn tx_gas(&self) -> u64 {
+        intrinsic_gas(&self.input) + self.gas
+    }
+}
+
+fn words(len: usize) -> u64 {
+    len.div_ceil(32) as u64
+}
+
+fn h(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap()
+}
+
+fn word(x: u64) -> Vec<u8> {
+    let mut buf = [0u8; 32];
+    U256::from(x).to_big_endian(&mut buf);
+    buf.to_vec()
+}
+
+fn left_pad(data: Vec<u8>) -> Vec<u8> {
+    let mut buf = vec![0; 32 - data.len()];
+    buf.extend(data);
+    buf
+}
+
+/// Vectors of the Ethereum tests and EIPs of the precompiles
+#[allow(dead_code)]
+pub fn vectors() -> Vec<Vector> {
+    let abc = b"abc".to_vec();
+
+    // BN254 generator and 2 * generator
+    let g1 = [word(1), word(2)].concat();
+    let g1_double = h("030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+        15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4");
+
+    // EIP-152 test vector 5: blake2b("abc"), 12 rounds
+    let blake2f = [
+        h("0000000c"),
+        h("48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5\
+            d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b"),
+        [abc.clone(), vec![0; 125]].concat(),
+        h("03000000000000000000000000000000"),
+        h("01"),
+    ]
+    .concat();
+
+    // EIP-4844: zero polynomial, the commitment and the proof are the point at infinity
+    let infinity = [vec![0xc0], vec![0; 47]].concat();
+    let point_evaluation = [
+        h("010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014"),
+        word(0),
+        word(0),
+        infinity.clone(),
+        infinity,
+    ]
+    .concat();
+
+    vec![
+        Vector {
+            name: "ecrecover".into(),
+            precompile: 1,
+            input: h("18c547e4f7b0f325ad1e56f57e26c745b09a3e503d86e00e5255ff7f715d3d1c\
+                000000000000000000000000000000000000000000000000000000000000001c\
+                73b1693892219d736caba55bdb67216e485557ea6b6af75f37096c9aa6a5a75f\
+                eeb940b1d03b21e36b0e47e79769f095fe2ab855bd91e3a38756b7d75a9c4549"),
+            output: Some(left_pad(h("a94f5374fce5edbc8e2a8697c15331677e6ebf0b"))),
+            gas: 3_000,
+        },
+        Vector {
+            name: "ecrecover_invalid_v".into(),
+            precompile: 1,
+            input: [word(1), word(0), word(1), word(1)].concat(),
+            output: Some(vec![]),
+            gas: 3_000,
+        },
+        Vector {
+            name: "sha256_empty".into(),
+            precompile: 2,
+            input: vec![],
+            output: Some(h("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")),
+            gas: 60,
+        },
+        Vector {
+            name: "sha256".into(),
+            precompile: 2,
+            input: abc.clone(),
+            output: Some(h("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")),
+            gas: 60 + 12 * words(abc.len()),
+        },
+        Vector {
+            name: "ripemd160_empty".into(),
+            precompile: 3,
+            input: vec![],
+            output: Some(left_pad(h("9c1185a5c5e9fc54612808977ee8f548b2258d31"))),
+            gas: 600,
+        },
+        Vector {
+            name: "ripemd160".into(),
+            precompile: 3,
+            input: abc.clone(),
+            output: Some(left_pad(h("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"))),
+            gas: 600 + 120 * words(abc.len()),
+        },
+        Vector {
+            name: "identity".into(),
+            precompile: 4,
+            input: (0..100u8).collect(),
+            output: Some((0..100u8).collect()),
+            gas: 15 + 3 * words(100),
+        },
+        // EIP-198: 3 ** (p - 1) mod p = 1, p is the secp256k1 field modulus
+        Vector {
+            name: "modexp".into(),
+            precompile: 5,
+            input: [
+                word(1),
+                word(32),
+                word(32),
+                h("03"),
+                h("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e"),
+                h("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+            ]
+            .concat(),
+            output: Some(word(1)),
+            gas: 1_360,
+        },
+        // EIP-2565 minimum price: 2 ** 3 mod 5
+        Vector {
+            name: "modexp_small".into(),
+            precompile: 5,
+            input: [word(1), word(1), word(1), h("02"), h("03"), h("05")].concat(),
+            output: Some(h("03")),
+            gas: 200,
+        },
+        Vector {
+            name: "bn256_add".into(),
+            precompile: 6,
+            input: [g1.clone(), g1.clone()].concat(),
+            output: Some(g1_double.clone()),
+            gas: 150,
+        },
+        Vector {
+            name: "bn256_add_not_on_curve".into(),
+            precompile: 6,
+            input: [word(1), word(3), g1.clone()].concat(),
+            output: None,
+            gas: 150,
+        },
+        Vector {
+            name: "bn256_mul".into(),
+            precompile: 7,
+            input: [g1, word(2)].concat(),
+            output: Some(g1_double),
+            gas: 6_000,
+        },
+        Vector {
+            name: "bn256_pairing_empty".into(),
+            precompile: 8,
+            input: vec![],
+            output: Some(word(1)),
+            gas: 45_000,
+        },
+        Vector {
+            name: "blake2f".into(),
+            precompile: 9,
+            input: blake2f,
+            output: Some(h("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+                7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923")),
+            gas: 12,
+        },
+        Vector {
+            name: "point_evaluation".into(),
+            precompile: 10,
+            input: point_evaluation,
+            output: Some(
+                [
+                    word(4096),
+                    h("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"),
+                ]
+                .concat(),
+            ),
+            gas: 50_000,
+        },
+        Vector {
+            name: "point_evaluation_invalid_length".into(),
+            precompile: 10,
+            input: vec![1; 32],
+            output: None,
+            gas: 50_000,
+        },
+    ]
+}
+
+/// Vector by name
+#[allow(dead_code)]
+pub fn vector(name: &str) -> Vector {
+    vectors()
+        .into_iter()
+        .find(|v| v.name == name)
+        .unwrap_or_else(|| panic!("vector {} not found", name))
+}
+
+/// Vector of the go-ethereum test data file
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GethVector {
+    input: String,
+    expected: String,
+    name: String,
+    gas: u64,
+}
+
+/// Vectors of the go-ethereum test data file of the precompile, e.g. blake2F.json.
+/// The files contain the successful calls only
+#[allow(dead_code)]
+pub fn load_vectors(file: &str, precompile: u8) -> Vec<Vector> {
+    let path = format!("{}{}", PRECOMPILE_VECTORS, file);
+    let content = fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e));
+    let vectors: Vec<GethVector> = serde_json::from_str(&content).unwrap();
+    assert!(!vectors.is_empty(), "no vectors in {}", path);
+
+    vectors
+        .into_iter()
+        .map(|v| Vector {
+            name: format!("{}:{}", file, v.name),
+            precompile,
+            input: h(&v.input),
+            output: Some(h(&v.expected)),
+            gas: v.gas,
+        })
+        .collect()
+}
+
+/// Gas of the precompile measured by PrecompileProbe: the gas of the staticcall
+/// without the call overhead, found by the identity call with the empty input
+#[allow(dead_code)]
+pub fn probe_gas(used: U256, identity_empty: U256) -> u64 {
+    (used.as_u64() + IDENTITY_EMPTY_GAS)
+        .checked_sub(identity_empty.as_u64())
+        .expect("the probe gas is below the baseline")
+}
@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, BTreeSet};
+use ethers_core::types::{TransactionReceipt, U256};
+use serde_json::Value;
+
+/// How the receipt field is compared between proxy and geth
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FieldPolicy {
+    /// the values are equal, a field missing on one side is a difference
+    Equal,
+    /// the field is not compared
+    Ignore,
+    /// numeric values differ by at most the given fraction of the larger one
+    Approx(f64),
+}
+
+/// L1 data fee fields of the OP-stack receipts. The rollup does not post the transactions to L1 as op-geth does,
+/// so the values are not comparable
+#[allow(dead_code)]
+pub const OP_STACK_L1_FIELDS: &[&str] = &[
+    "l1Fee",
+    "l1GasPrice",
+    "l1GasUsed",
+    "l1FeeScalar",
+    "l1BaseFeeScalar",
+    "l1BlobBaseFee",
+    "l1BlobBaseFeeScalar",
+];
+
+/// Receipt comparator with the per-field policies.
+/// The fields are named as in the JSON-RPC response, the fields without the policy use the default one
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct ReceiptComparator {
+    pub policies: BTreeMap<String, FieldPolicy>,
+    pub default: FieldPolicy,
+}
+
+impl Default for ReceiptComparator {
+    fn default() -> Self {
+        Self { policies: BTreeMap::new(), default: FieldPolicy::Equal }
+    }
+}
+
+#[allow(dead_code)]
+impl ReceiptComparator {
+    /// Every field must be equal, including the L1 fields
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// Policies of the OP-stack rollup: the L1 data fee fields are ignored,
+    /// the deposit fields and the rest of the receipt must be equal
+    pub fn op_stack() -> Self {
+        OP_STACK_L1_FIELDS
+            .iter()
+            .fold(Self::default(), |comparator, field| comparator.policy(field, FieldPolicy::Ignore))
+    }
+
+    pub fn policy(mut self, field: &str, policy: FieldPolicy) -> Self {
+        self.policies.insert(field.to_string(), policy);
+        self
+    }
+
+    pub fn default_policy(mut self, policy: FieldPolicy) -> Self {
+        self.default = policy;
+        self
+    }
+
+    /// Policy of the field, the default one if the field has no policy
+    pub fn policy_of(&self, field: &str) -> FieldPolicy {
+        self.policies.get(field).copied().unwrap_or(self.default)
+    }
+
+    /// Compare the receipts field by field, the L2 fields of `other` included.
+    /// Returns the differences
+    pub fn compare(&self, proxy: &TransactionReceipt, geth: &TransactionReceipt) -> Vec<String> {
+        let proxy = fields(proxy);
+        let geth = fields(geth);
+        let names: BTreeSet<&String> = proxy.keys().chain(geth.keys()).collect();
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let policy = self.policy_of(name);
+                let (a, b) = (proxy.get(name), geth.get(name));
+
+                let equal = match policy {
+                    FieldPolicy::Ignore => true,
+                    FieldPolicy::Equal => a == b,
+                    FieldPolicy::Approx(tolerance) => match (a.and_then(number), b.and_then(number)) {
+                        (Some(a), Some(b)) => approx(a, b, tolerance),
+                        _ => a == b,
+                    },
+                };
+
+                (!equal).then(|| format!("{}: proxy {:?}, geth {:?} ({:?})", name, a, b, policy))
+            })
+            .collect()
+    }
+}
+
+/// Fields of the receipt in the JSON-RPC form, the null fields are dropped
+fn fields(receipt: &TransactionReceipt) -> BTreeMap<String, Value> {
+    match serde_json::to_value(receipt).unwrap() {
+        Value::Object(map) => map.into_iter().filter(|(_, value)| !value.is_null()).collect(),
+        _ => unreachable!("receipt is serialized as an object"),
+    }
+}
+
+/// Numeric value of the field: hex or decimal string, or number
+#[allow(dead_code)]
+pub fn number(value: &Value) -> Option<U256> {
+    match value {
+        Value::String(s) if s.starts_with("0x") => U256::from_str_radix(&s[2..], 16).ok(),
+        Value::String(s) => U256::from_dec_str(s).ok(),
+        Value::Number(n) => n.as_u64().map(U256::from),
+        _ => None,
+    }
+}
+
+/// Precision of the tolerance, parts per million
+const PPM: u64 = 1_000_000;
+
+/// The values differ by at most the fraction of the larger one
+#[allow(dead_code)]
+pub fn approx(a: U256, b: U256, tolerance: f64) -> bool {
+    let (max, diff) = if a > b { (a, a - b) } else { (b, b - a) };
+    diff.saturating_mul(PPM.into()) <= max.saturating_mul(((tolerance * PPM as f64) as u64).into())
+}
+
@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use rome_sdk::rome_evm_client::emulator::Instruction;
+
+/// Execution path of the EVM transaction in rome-evm
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecutionMode {
+    /// single DoTx instruction
+    Atomic,
+    /// sequence of DoTxIterative instructions
+    Iterative,
+    /// calldata is transmitted to the holder account, then DoTxHolder
+    AtomicHolder,
+    /// calldata is transmitted to the holder account, then DoTxHolderIterative
+    IterativeHolder,
+}
+
+#[allow(dead_code)]
+impl ExecutionMode {
+    /// The calldata is too large for a solana transaction
+    pub fn is_holder(&self) -> bool {
+        matches!(self, ExecutionMode::AtomicHolder | ExecutionMode::IterativeHolder)
+    }
+
+    pub fn is_iterative(&self) -> bool {
+        matches!(self, ExecutionMode::Iterative | ExecutionMode::IterativeHolder)
+    }
+}
+
+/// Solana transaction produced by the EVM transaction
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct SolanaTx {
+    pub signature: Signature,
+    /// fee payer of the transaction
+    pub payer: Pubkey,
+    pub slot: u64,
+    /// tag of the rome-evm instruction, None if the transaction does not call rome-evm
+    pub instruction: Option<u8>,
+    /// accounts of the rome-evm instruction and their writable flags
+    pub accounts: Vec<(Pubkey, bool)>,
+    /// compute units consumed by the transaction
+    pub compute_units: u64,
+    /// lamports paid by the fee payer
+    pub fee: u64,
+}
+
+/// Solana resources used by the EVM transaction
+#[allow(dead_code)]
+#[derive(Clone, Debug, Default)]
+pub struct TxReport {
+    /// solana transactions in the order of execution
+    pub solana_txs: Vec<SolanaTx>,
+}
+
+#[allow(dead_code)]
+impl TxReport {
+    pub fn signatures(&self) -> Vec<Signature> {
+        self.solana_txs.iter().map(|tx| tx.signature).collect()
+    }
+
+    /// Payers of the solana transactions
+    pub fn payers(&self) -> BTreeSet<Pubkey> {
+        self.solana_txs.iter().map(|tx| tx.payer).collect()
+    }
+
+    /// Number of solana transactions
+    pub fn count(&self) -> usize {
+        self.solana_txs.len()
+    }
+
+    /// The EVM transaction is executed by a single rome-evm instruction
+    pub fn is_atomic(&self) -> bool {
+        !self.mode().is_iterative()
+    }
+
+    /// Execution path taken by rome-evm, found by the instructions of the solana transactions
+    pub fn mode(&self) -> ExecutionMode {
+        let has = |ix: Instruction| self.instructions().contains(&(ix as u8));
+
+        let holder = has(Instruction::TransmitTx)
+            || has(Instruction::DoTxHolder)
+            || has(Instruction::DoTxHolderIterative);
+
+        match (holder, self.iterations() > 0) {
+            (false, false) => ExecutionMode::Atomic,
+            (false, true) => ExecutionMode::Iterative,
+            (true, false) => ExecutionMode::AtomicHolder,
+            (true, true) => ExecutionMode::IterativeHolder,
+        }
+    }
+
+    /// Number of iterations of the iterative execution
+    pub fn iterations(&self) -> usize {
+        self.instructions()
+            .iter()
+            .filter(|&&tag| {
+                tag == Instruction::DoTxIterative as u8 || tag == Instruction::DoTxHolderIterative as u8
+            })
+            .count()
+    }
+
+    /// Tags of the rome-evm instructions
+    pub fn instructions(&self) -> Vec<u8> {
+        self.solana_txs.iter().filter_map(|tx| tx.instruction).collect()
+    }
+
+    /// Check the execution path declared by the test case
+    pub fn assert_mode(&self, expected: ExecutionMode) {
+        assert_eq!(
+            self.mode(),
+            expected,
+            "unexpected execution mode, instructions: {:?}",
+            self.instructions(),
+        );
+    }
+
+    /// Total compute units
+    pub fn compute_units(&self) -> u64 {
+        self.solana_txs.iter().map(|tx| tx.compute_units).sum()
+    }
+
+    /// Total lamports paid
+    pub fn lamports(&self) -> u64 {
+        self.solana_txs.iter().map(|tx| tx.fee).sum()
+    }
+
+    /// Print the report, the line is parsed to track the resources between the runs
+    pub fn log(&self, name: &str) {
+        println!(
+            "[ Resources ] - {}: mode {:?}, iterations {}, solana_txs {}, compute_units {}, lamports {}",
+            name,
+            self.mode(),
+            self.iterations(),
+            self.count(),
+            self.compute_units(),
+            self.lamports(),
+        );
+    }
+}
+
+/// Load the solana transactions of the EVM transaction by the signatures returned by the sdk
+#[allow(dead_code)]
+pub async fn load_report(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signatures: &[Signature],
+) -> TxReport {
+    let mut txs = vec![];
+    for signature in signatures {
+        txs.push(load_tx(rpc, program_id, *signature).await);
+    }
+    txs.sort_by_key(|tx| tx.slot);
+
+    TxReport { solana_txs: txs }
+}
+
+#[allow(dead_code)]
+async fn load_tx(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    signature: Signature,
+) -> SolanaTx {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed = rpc
+        .get_transaction_with_config(&signature, config)
+        .await
+        .unwrap();
+
+    let meta = confirmed.transaction.meta.expect("transaction meta not found");
+    let compute_units: Option<u64> = meta.compute_units_consumed.into();
+    let tx = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .expect("transaction is not decoded");
+
+    let keys = tx.message.static_account_keys().to_vec();
+    let header = *tx.message.header();
+    let signed = header.num_required_signatures as usize;
+    let writable = |i: usize| {
+        if i < signed {
+            i < signed - header.num_readonly_signed_accounts as usize
+        } else {
+            i < keys.len() - header.num_readonly_unsigned_accounts as usize
+        }
+    };
+
+    let (instruction, accounts) = tx
+        .message
+        .instructions()
+        .iter()
+        .find(|ix| keys.get(ix.program_id_index as usize) == Some(program_id))
+        .map(|ix| {
+            let accounts = ix
+                .accounts
+                .iter()
+                .filter_map(|&i| keys.get(i as usize).map(|key| (*key, writable(i as usize))))
+                .collect();
+            (ix.data.first().copied(), accounts)
+        })
+        .unwrap_or_default();
+
+    SolanaTx {
+        signature,
+        // the fee payer is the first account of the message
+        payer: keys[0],
+        slot: confirmed.slot,
+        instruction,
+        accounts,
+        compute_units: compute_units.unwrap_or_default(),
+        fee: meta.fee,
+    }
+}
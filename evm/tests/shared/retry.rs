@@ -0,0 +1,137 @@
+use std::{env, fmt::Debug, fs::OpenOptions, future::Future, io::Write, time::Duration};
+
+/// Delay between the attempts
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub enum Backoff {
+    /// the next attempt starts immediately
+    None,
+    Fixed(Duration),
+    /// the delay is doubled after every attempt up to the max
+    Exponential { initial: Duration, max: Duration },
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// maximum number of attempts, including the first one
+    pub attempts: usize,
+    pub backoff: Backoff,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Backoff::Exponential {
+                initial: Duration::from_secs(1),
+                max: Duration::from_secs(8),
+            },
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl RetryPolicy {
+    pub fn new(attempts: usize, backoff: Backoff) -> Self {
+        assert!(attempts > 0);
+        Self { attempts, backoff }
+    }
+
+    /// Delay after the failed attempt, attempts are counted from 1
+    pub fn delay(&self, attempt: usize) -> Duration {
+        match self.backoff {
+            Backoff::None => Duration::ZERO,
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 1u32 << (attempt - 1).min(16);
+                initial.saturating_mul(factor).min(max)
+            },
+        }
+    }
+}
+
+/// Kind of the error returned by the attempt
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// timing issue, the next attempt may succeed
+    Transient,
+    /// real failure, the retries are stopped
+    Permanent,
+}
+
+/// Error that stopped the retries
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct RetryError<E> {
+    pub error: E,
+    pub class: ErrorClass,
+    pub attempts: usize,
+}
+
+/// Call f until it returns Ok, the error is classified as permanent or the attempts are exhausted
+#[allow(dead_code)]
+pub async fn retry_classified<F, Fut, T, E, C>(
+    name: &str,
+    policy: &RetryPolicy,
+    classifier: C,
+    mut f: F,
+) -> Result<T, RetryError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Debug,
+    C: Fn(&E) -> ErrorClass,
+{
+    let mut attempt = 1;
+
+    loop {
+        println!("Attempt {}...", attempt);
+        let error = match f().await {
+            Ok(result) => {
+                record(name, attempt, None);
+                return Ok(result);
+            },
+            Err(error) => error,
+        };
+
+        let class = classifier(&error);
+        println!("Attempt {} failed, {:?} error: {:?}", attempt, class, error);
+
+        if class == ErrorClass::Permanent || attempt >= policy.attempts {
+            record(name, attempt, Some(class));
+            return Err(RetryError { error, class, attempts: attempt });
+        }
+
+        async_std::task::sleep(policy.delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Flakiness record of the test:
+///  - passed with attempts > 1 is a timing flake,
+///  - failed with the permanent error is a real failure.
+/// The record is printed and appended to the file set by the FLAKINESS_REPORT env variable
+fn record(name: &str, attempts: usize, error: Option<ErrorClass>) {
+    let result = match error {
+        None => "passed",
+        Some(ErrorClass::Transient) => "transient",
+        Some(ErrorClass::Permanent) => "permanent",
+    };
+    println!("[ Flakiness ] - {}: attempts {}, result {}", name, attempts, result);
+
+    if let Ok(path) = env::var("FLAKINESS_REPORT") {
+        let line = serde_json::json!({
+            "test": name,
+            "attempts": attempts,
+            "result": result,
+        });
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .unwrap_or_else(|e| panic!("failed to open {}: {}", path, e));
+        writeln!(file, "{}", line).unwrap();
+    }
+}
@@ -0,0 +1,432 @@
+use std::{env, fs, path::Path};
+use ethers::providers::{Http, Provider, ProviderError, RpcError};
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{H160, H256};
+use ethers_signers::{Signer, Wallet};
+use serde_json::{json, Value};
+use super::utils::{check_recipt, deploy_contract};
+
+/// Error code of the method that is not supported
+#[allow(dead_code)]
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+/// JSON-RPC request of the conformance matrix
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct RpcCase {
+    pub method: &'static str,
+    pub params: Value,
+    /// params with the values of the context replaced by their names
+    pub label: String,
+    /// the params are valid, a result is expected
+    pub valid: bool,
+    /// the difference from geth fails the test, other differences are only reported
+    pub required: bool,
+}
+
+impl RpcCase {
+    fn new(method: &'static str, params: Value, valid: bool) -> Self {
+        let label = params.to_string();
+        Self { method, params, label, valid, required: false }
+    }
+
+    fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+}
+
+/// Objects known to both providers, used as the params
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct RpcContext {
+    pub address: H160,
+    pub contract: H160,
+    pub block_number: u64,
+    pub block_hash: H256,
+    pub tx_hash: H256,
+}
+
+#[allow(dead_code)]
+impl RpcContext {
+    /// Deploy the contract through the provider, the deployment transaction and its block are the context
+    pub async fn prepare(contract: &str, provider_name: &str, sender: &Wallet<SigningKey>) -> Self {
+        let (contract, recipt) = deploy_contract(contract, provider_name, sender).await.unwrap();
+        check_recipt(&recipt).await;
+
+        Self {
+            address: sender.address(),
+            contract: contract.address(),
+            block_number: recipt.block_number.unwrap().as_u64(),
+            block_hash: recipt.block_hash.unwrap(),
+            tx_hash: recipt.transaction_hash,
+        }
+    }
+}
+
+/// Response of the provider
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RpcResult {
+    /// shape of the result, see [shape]
+    Ok(Value),
+    Err { code: i64, message: String },
+    /// the request failed without the JSON-RPC error
+    Transport(String),
+}
+
+impl RpcResult {
+    fn describe(&self) -> String {
+        match self {
+            RpcResult::Ok(shape) => format!("ok {}", shape),
+            RpcResult::Err { code, message } => format!("error {} {}", code, message),
+            RpcResult::Transport(e) => format!("transport {}", e),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RpcStatus {
+    Pass,
+    /// the proxy responds with METHOD_NOT_FOUND, geth does not
+    Missing,
+    /// both results are ok, the shapes differ
+    ShapeDiffers,
+    /// one provider returns a result, the other one an error, or the error codes differ
+    ErrorDiffers,
+    /// the error codes are equal, the messages differ. The messages of the implementations never match,
+    /// the difference is only reported
+    MessageDiffers,
+}
+
+impl RpcStatus {
+    fn as_str(&self) -> &str {
+        match self {
+            RpcStatus::Pass => "pass",
+            RpcStatus::Missing => "missing",
+            RpcStatus::ShapeDiffers => "shape_differs",
+            RpcStatus::ErrorDiffers => "error_differs",
+            RpcStatus::MessageDiffers => "message_differs",
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct RpcRow {
+    pub case: RpcCase,
+    pub proxy: RpcResult,
+    pub geth: RpcResult,
+    pub status: RpcStatus,
+}
+
+/// Fields of the blocks, the transactions and the receipts served by op-geth only:
+/// the Cancun and Prague header fields, the deposit transaction fields and the L1 data fee of the OP-stack.
+/// They are optional, the shape does not include them
+const OPTIONAL_FIELDS: &[&str] = &[
+    "withdrawals",
+    "withdrawalsRoot",
+    "blobGasUsed",
+    "excessBlobGas",
+    "parentBeaconBlockRoot",
+    "requestsHash",
+    "blobGasPrice",
+    "blobVersionedHashes",
+    "maxFeePerBlobGas",
+    "sourceHash",
+    "mint",
+    "isSystemTx",
+    "depositReceiptVersion",
+    "depositNonce",
+    "l1Fee",
+    "l1GasPrice",
+    "l1GasUsed",
+    "l1FeeScalar",
+    "l1BaseFeeScalar",
+    "l1BlobBaseFee",
+    "l1BlobBaseFeeScalar",
+];
+
+/// Type structure of the JSON value: the leaves are replaced by the type names,
+/// an array is described by the distinct shapes of its elements, so the length is not compared.
+/// The OPTIONAL_FIELDS of the objects are skipped
+#[allow(dead_code)]
+pub fn shape(value: &Value) -> Value {
+    match value {
+        Value::Null => json!("null"),
+        Value::Bool(_) => json!("bool"),
+        Value::Number(_) => json!("number"),
+        Value::String(s) if s.starts_with("0x") => json!("hex"),
+        Value::String(_) => json!("string"),
+        Value::Array(items) => {
+            let mut shapes: Vec<Value> = items.iter().map(shape).collect();
+            shapes.sort_by_key(|shape| shape.to_string());
+            shapes.dedup();
+            Value::Array(shapes)
+        },
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(k, _)| !OPTIONAL_FIELDS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), shape(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Send the raw JSON-RPC request
+#[allow(dead_code)]
+pub async fn call(provider: &Provider<Http>, method: &str, params: &Value) -> RpcResult {
+    match provider.request::<_, Value>(method, params.clone()).await {
+        Ok(value) => RpcResult::Ok(shape(&value)),
+        Err(e) => error(e),
+    }
+}
+
+fn error(e: ProviderError) -> RpcResult {
+    match e.as_error_response() {
+        Some(e) => RpcResult::Err { code: e.code, message: e.message.clone() },
+        None => RpcResult::Transport(e.to_string()),
+    }
+}
+
+#[allow(dead_code)]
+pub fn compare(proxy: &RpcResult, geth: &RpcResult) -> RpcStatus {
+    match (proxy, geth) {
+        (RpcResult::Err { code, .. }, geth) if *code == METHOD_NOT_FOUND => match geth {
+            RpcResult::Err { code, .. } if *code == METHOD_NOT_FOUND => RpcStatus::Pass,
+            _ => RpcStatus::Missing,
+        },
+        (RpcResult::Ok(a), RpcResult::Ok(b)) if a == b => RpcStatus::Pass,
+        (RpcResult::Ok(_), RpcResult::Ok(_)) => RpcStatus::ShapeDiffers,
+        (RpcResult::Err { code: a, message: m1 }, RpcResult::Err { code: b, message: m2 }) if a == b => {
+            if m1 == m2 { RpcStatus::Pass } else { RpcStatus::MessageDiffers }
+        },
+        _ => RpcStatus::ErrorDiffers,
+    }
+}
+
+/// Pass/fail matrix of the cases
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct RpcMatrix {
+    pub rows: Vec<RpcRow>,
+}
+
+#[allow(dead_code)]
+impl RpcMatrix {
+    /// Send every case to both providers
+    pub async fn run(proxy: &Provider<Http>, geth: &Provider<Http>, ctx: &RpcContext) -> Self {
+        let mut matrix = Self::default();
+
+        for case in cases(ctx) {
+            let proxy = call(proxy, case.method, &case.params).await;
+            let geth = call(geth, case.method, &case.params).await;
+            let status = compare(&proxy, &geth);
+            println!("[ Rpc ] - {} {}: {}", case.method, case.params, status.as_str());
+            if status != RpcStatus::Pass {
+                println!("    proxy: {}\n    geth:  {}", proxy.describe(), geth.describe());
+            }
+            matrix.rows.push(RpcRow { case, proxy, geth, status });
+        }
+
+        matrix
+    }
+
+    /// Required cases that differ from geth, only the error codes of the required cases are compared
+    pub fn failures(&self) -> Vec<&RpcRow> {
+        self.rows
+            .iter()
+            .filter(|row| row.case.required && !matches!(row.status, RpcStatus::Pass | RpcStatus::MessageDiffers))
+            .collect()
+    }
+
+    /// Methods the proxy does not support
+    pub fn missing(&self) -> Vec<&str> {
+        let mut methods: Vec<&str> = self
+            .rows
+            .iter()
+            .filter(|row| row.status == RpcStatus::Missing)
+            .map(|row| row.case.method)
+            .collect();
+        methods.sort();
+        methods.dedup();
+        methods
+    }
+
+    /// One line per case, the case is identified by the method and the label.
+    /// The label does not include the values of the context, so the matrix of different runs can be compared with diff
+    pub fn csv(&self) -> String {
+        let mut csv = "method,label,params,required,status,proxy,geth\n".to_string();
+        for row in &self.rows {
+            let params = if row.case.valid { "valid" } else { "invalid" };
+            csv += &format!(
+                "{},\"{}\",{},{},{},\"{}\",\"{}\"\n",
+                row.case.method,
+                row.case.label.replace('"', "'"),
+                params,
+                row.case.required,
+                row.status.as_str(),
+                row.proxy.describe().replace('"', "'"),
+                row.geth.describe().replace('"', "'"),
+            );
+        }
+        csv
+    }
+
+    pub fn json(&self) -> Value {
+        let rows = self.rows.iter().map(|row| {
+            json!({
+                "method": row.case.method,
+                "label": row.case.label,
+                "params": if row.case.valid { "valid" } else { "invalid" },
+                "required": row.case.required,
+                "status": row.status.as_str(),
+                "proxy": row.proxy.describe(),
+                "geth": row.geth.describe(),
+            })
+        });
+        Value::Array(rows.collect())
+    }
+
+    /// The matrix is printed as CSV and written to the file set by the RPC_MATRIX env variable,
+    /// the format is chosen by the extension: .json or .csv
+    pub fn write(&self) {
+        println!("{}", self.csv());
+
+        if let Ok(path) = env::var("RPC_MATRIX") {
+            let content = match Path::new(&path).extension().and_then(|e| e.to_str()) {
+                Some("json") => serde_json::to_string_pretty(&self.json()).unwrap(),
+                _ => self.csv(),
+            };
+            fs::write(&path, content).unwrap_or_else(|e| panic!("failed to write {}: {}", path, e));
+        }
+    }
+}
+
+/// Standard eth_*, net_* and web3_* methods with valid and invalid params
+#[allow(dead_code)]
+pub fn cases(ctx: &RpcContext) -> Vec<RpcCase> {
+    let address = json!(ctx.address);
+    let contract = json!(ctx.contract);
+    let number = json!(format!("0x{:x}", ctx.block_number));
+    let hash = json!(ctx.block_hash);
+    let tx = json!(ctx.tx_hash);
+    let unknown = json!(H256::repeat_byte(0xab));
+    let call = json!({ "from": ctx.address, "to": ctx.contract, "data": "0x" });
+    let transfer = json!({ "from": ctx.address, "to": ctx.address, "value": "0x1" });
+    let filter = json!({ "fromBlock": number, "toBlock": number });
+
+    let names = [
+        (&address, "address"),
+        (&contract, "contract"),
+        (&number, "number"),
+        (&hash, "hash"),
+        (&tx, "tx"),
+        (&unknown, "unknown"),
+    ];
+
+    let cases = vec![
+        // web3 and net
+        RpcCase::new("web3_clientVersion", json!([]), true),
+        RpcCase::new("web3_sha3", json!(["0x68656c6c6f"]), true).required(),
+        RpcCase::new("web3_sha3", json!(["hello"]), false),
+        RpcCase::new("net_version", json!([]), true).required(),
+        RpcCase::new("net_listening", json!([]), true),
+        RpcCase::new("net_peerCount", json!([]), true),
+
+        // chain state
+        RpcCase::new("eth_chainId", json!([]), true).required(),
+        RpcCase::new("eth_syncing", json!([]), true),
+        RpcCase::new("eth_coinbase", json!([]), true),
+        RpcCase::new("eth_mining", json!([]), true),
+        RpcCase::new("eth_hashrate", json!([]), true),
+        RpcCase::new("eth_accounts", json!([]), true),
+        RpcCase::new("eth_blockNumber", json!([]), true).required(),
+        RpcCase::new("eth_gasPrice", json!([]), true).required(),
+        RpcCase::new("eth_maxPriorityFeePerGas", json!([]), true),
+        RpcCase::new("eth_blobBaseFee", json!([]), true),
+        RpcCase::new("eth_feeHistory", json!(["0x1", "latest", [50]]), true),
+        RpcCase::new("eth_feeHistory", json!(["latest"]), false),
+
+        // accounts
+        RpcCase::new("eth_getBalance", json!([address, "latest"]), true).required(),
+        RpcCase::new("eth_getBalance", json!(["0x1234", "latest"]), false).required(),
+        RpcCase::new("eth_getTransactionCount", json!([address, "latest"]), true).required(),
+        RpcCase::new("eth_getTransactionCount", json!([address, "unknown_tag"]), false),
+        RpcCase::new("eth_getCode", json!([contract, "latest"]), true).required(),
+        RpcCase::new("eth_getCode", json!([]), false),
+        RpcCase::new("eth_getStorageAt", json!([contract, "0x0", "latest"]), true).required(),
+        RpcCase::new("eth_getStorageAt", json!([contract, "latest"]), false),
+        RpcCase::new("eth_getProof", json!([contract, ["0x0"], "latest"]), true),
+
+        // blocks
+        RpcCase::new("eth_getBlockByNumber", json!([number, false]), true).required(),
+        RpcCase::new("eth_getBlockByNumber", json!([number, true]), true).required(),
+        RpcCase::new("eth_getBlockByNumber", json!(["0xffffffffff", false]), true).required(),
+        RpcCase::new("eth_getBlockByNumber", json!(["latest"]), false),
+        RpcCase::new("eth_getBlockByHash", json!([hash, false]), true).required(),
+        RpcCase::new("eth_getBlockByHash", json!([unknown, false]), true).required(),
+        RpcCase::new("eth_getBlockByHash", json!(["0x12", false]), false),
+        RpcCase::new("eth_getBlockTransactionCountByNumber", json!([number]), true).required(),
+        RpcCase::new("eth_getBlockTransactionCountByHash", json!([hash]), true).required(),
+        RpcCase::new("eth_getUncleCountByBlockNumber", json!([number]), true),
+        RpcCase::new("eth_getUncleCountByBlockHash", json!([hash]), true),
+        RpcCase::new("eth_getUncleByBlockNumberAndIndex", json!([number, "0x0"]), true),
+        RpcCase::new("eth_getUncleByBlockHashAndIndex", json!([hash, "0x0"]), true),
+        RpcCase::new("eth_getBlockReceipts", json!([number]), true),
+
+        // transactions
+        RpcCase::new("eth_getTransactionByHash", json!([tx]), true).required(),
+        RpcCase::new("eth_getTransactionByHash", json!([unknown]), true).required(),
+        RpcCase::new("eth_getTransactionByHash", json!(["0x12"]), false),
+        RpcCase::new("eth_getTransactionReceipt", json!([tx]), true).required(),
+        RpcCase::new("eth_getTransactionReceipt", json!([unknown]), true).required(),
+        RpcCase::new("eth_getTransactionByBlockNumberAndIndex", json!([number, "0x0"]), true).required(),
+        RpcCase::new("eth_getTransactionByBlockHashAndIndex", json!([hash, "0x0"]), true).required(),
+        RpcCase::new("eth_sendRawTransaction", json!(["0x1234"]), false).required(),
+        RpcCase::new("eth_sendTransaction", json!([transfer]), false),
+        RpcCase::new("eth_sign", json!([address, "0x1234"]), false),
+        RpcCase::new("eth_signTransaction", json!([transfer]), false),
+
+        // execution
+        RpcCase::new("eth_call", json!([call, "latest"]), true).required(),
+        RpcCase::new("eth_call", json!([{ "to": "0x12" }, "latest"]), false),
+        RpcCase::new("eth_estimateGas", json!([transfer]), true).required(),
+        RpcCase::new("eth_estimateGas", json!([{ "from": ctx.address, "value": "0xffffffffffffffffffffffff" }]), false),
+        RpcCase::new("eth_createAccessList", json!([call, "latest"]), true),
+
+        // logs and filters
+        RpcCase::new("eth_getLogs", json!([filter]), true).required(),
+        RpcCase::new("eth_getLogs", json!([{ "fromBlock": "0x2", "toBlock": "0x1" }]), false),
+        RpcCase::new("eth_newFilter", json!([filter]), true),
+        RpcCase::new("eth_newBlockFilter", json!([]), true),
+        RpcCase::new("eth_newPendingTransactionFilter", json!([]), true),
+        RpcCase::new("eth_getFilterChanges", json!(["0x123456"]), false),
+        RpcCase::new("eth_getFilterLogs", json!(["0x123456"]), false),
+        RpcCase::new("eth_uninstallFilter", json!(["0x123456"]), true),
+
+        // unknown method
+        RpcCase::new("eth_unknownMethod", json!([]), false).required(),
+    ];
+
+    cases
+        .into_iter()
+        .map(|mut case| {
+            case.label = label(&case.params, &names).to_string();
+            case
+        })
+        .collect()
+}
+
+/// The values of the context in the params are replaced by their names
+fn label(params: &Value, names: &[(&Value, &str)]) -> Value {
+    if let Some((_, name)) = names.iter().find(|(value, _)| *value == params) {
+        return json!(format!("<{}>", name));
+    }
+    match params {
+        Value::Array(items) => Value::Array(items.iter().map(|item| label(item, names)).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), label(v, names))).collect()),
+        value => value.clone(),
+    }
+}
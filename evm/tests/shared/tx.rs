@@ -23,6 +23,20 @@ pub fn calc_address(client: &Client, from: &Address) -> Address {
     Address::from(address)
 }
 
+/// Address of the contract created by CREATE2:
+/// keccak256(0xff ++ factory ++ salt ++ keccak256(init_code))[12..]
+#[allow(dead_code)]
+pub fn calc_create2_address(factory: &Address, salt: [u8; 32], init_code: &[u8]) -> Address {
+    let mut buf = vec![0xff];
+    buf.extend_from_slice(factory.as_bytes());
+    buf.extend_from_slice(&salt);
+    buf.extend_from_slice(&hash(init_code).to_bytes());
+
+    let hash = hash(&buf);
+    let address: [u8; 20] = hash.to_bytes()[12..].try_into().unwrap();
+    Address::from(address)
+}
+
 #[allow(dead_code)]
 pub fn do_tx(
     client: &Client,
@@ -45,6 +59,36 @@ pub fn do_tx_base(
     value: U256,
     tx_type: u8,
     nonce: u64,
+) -> TypedTransaction {
+    build_tx(client, to, data, wallet, value, tx_type, nonce, None)
+}
+
+/// Same as [do_tx], but the gas limit is set by the caller instead of estimate_gas.
+/// Used for transactions that are expected to fail
+#[allow(dead_code)]
+pub fn do_tx_with_gas(
+    client: &Client,
+    to: Option<Address>,
+    data: Vec<u8>,
+    wallet: &Wallet<SigningKey>,
+    value: U256,
+    tx_type: u8,
+    gas: U256,
+) -> TypedTransaction {
+    let nonce = client.transaction_count(wallet.address()).unwrap().as_u64();
+    build_tx(client, to, data, wallet, value, tx_type, nonce, Some(gas))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_tx(
+    client: &Client,
+    to: Option<Address>,
+    data: Vec<u8>,
+    wallet: &Wallet<SigningKey>,
+    value: U256,
+    tx_type: u8,
+    nonce: u64,
+    gas: Option<U256>,
 ) -> TypedTransaction {
     println!("nonce: {}", nonce);
 
@@ -62,7 +106,7 @@ pub fn do_tx_base(
                 ..Default::default()
             };
             legacy.from = Some(wallet.address());
-            legacy.gas = Some(client.estimate_gas(&legacy).unwrap());
+            legacy.gas = Some(gas.unwrap_or_else(|| client.estimate_gas(&legacy).unwrap()));
             TypedTransaction::Legacy(legacy)
         },
         2 => {
@@ -78,7 +122,7 @@ pub fn do_tx_base(
             };
             let mut legacy: TransactionRequest = eip1559.clone().into();
             legacy.from = Some(wallet.address());
-            eip1559.gas = Some(client.estimate_gas(&legacy).unwrap());
+            eip1559.gas = Some(gas.unwrap_or_else(|| client.estimate_gas(&legacy).unwrap()));
             TypedTransaction::Eip1559(eip1559)
         },
         _ => unimplemented!()
@@ -87,6 +131,12 @@ pub fn do_tx_base(
 
 
 
+/// Base cost of the transaction and the calldata cost
+#[allow(dead_code)]
+pub fn intrinsic_gas(data: &[u8]) -> u64 {
+    21_000 + data.iter().map(|&b| if b == 0 { 4 } else { 16 }).sum::<u64>()
+}
+
 #[allow(dead_code)]
 pub fn do_rlp(
     tx: &TypedTransaction,
@@ -1,4 +1,6 @@
 use crate::shared::config::{load_config, Config};
+use crate::shared::overrides::{BlockOverrides, StateOverride};
+use crate::shared::receipt::ReceiptComparator;
 use crate::shared::{
     client_config, CLIENT_CONFIG_PATH, PROXY_CONFIG_PATH, RECEIVER_PK, RHEA_CONFIG_PATH,
 };
@@ -14,7 +16,7 @@ use {
         contract::{Contract, ContractFactory, ContractInstance},
         core::types::TransactionRequest,
         middleware::SignerMiddleware,
-        providers::{Http, Middleware, Provider},
+        providers::{Http, Middleware, PendingTransaction, Provider, ProviderError},
     },
     ethers_core::{
         abi::Abi,
@@ -25,8 +27,6 @@ use {
         types::{transaction::eip2718::TypedTransaction, Bytes, Eip1559TransactionRequest, U256},
     },
     ethers_signers::{LocalWallet, Signer, Wallet},
-    std::future::Future,
-    std::panic::{catch_unwind, AssertUnwindSafe},
     std::time::Instant,
     std::{env, str::FromStr, sync::Arc},
 };
@@ -302,70 +302,6 @@ pub async fn deploy_contract(
     Ok(contract)
 }
 
-#[allow(dead_code)]
-pub async fn retry<F, Fut, T, E>(mut f: F) -> Result<T, E>
-where
-    F: FnMut() -> Fut,
-    Fut: Future<Output = Result<T, E>>,
-{
-    let mut attempts = 0;
-    while attempts < 3 {
-        println!("Attempt {}...", attempts + 1);
-        match f().await {
-            Ok(result) => {
-                println!("Attempt {} succeeded.", attempts + 1);
-                return Ok(result);
-            }
-            Err(_) if attempts < 2 => {
-                println!("Attempt {} failed. Retrying...", attempts + 1);
-                attempts += 1;
-            }
-            Err(e) => {
-                println!("Attempt {} failed. No more retries.", attempts + 1);
-                return Err(e);
-            }
-        }
-    }
-    let final_err = f().await.err().unwrap();
-    println!("All attempts failed.");
-    Err(final_err)
-}
-
-#[allow(dead_code)]
-pub async fn retry_panic<F, Fut, T>(mut f: F) -> Result<(), ()>
-where
-    F: FnMut() -> Fut,
-    Fut: Future<Output = T>,
-{
-    let mut attempts = 0;
-
-    while attempts < 6 {
-        println!("Attempt {}...", attempts + 1);
-
-        let result = catch_unwind(AssertUnwindSafe(|| futures::executor::block_on(f())));
-
-        match result {
-            Err(_) => {
-                println!(
-                    "Test panicked on attempt {}. Expected behavior, stopping retries.",
-                    attempts + 1
-                );
-                panic!("The test panicked as expected");
-            }
-            Ok(_) if attempts < 2 => {
-                println!("Attempt {} did NOT panic. Retrying...", attempts + 1);
-                attempts += 1;
-            }
-            Ok(_) => {
-                println!("All attempts completed without panic. Test FAILED (expected panic).");
-                return Ok(()); // No panic after 3 tries => test should fail
-            }
-        }
-    }
-
-    Ok(())
-}
-
 #[allow(dead_code)]
 pub async fn check_storage(
     provider: Provider<Http>,
@@ -507,13 +443,29 @@ pub async fn check_recipt(recipt: &TransactionReceipt) {
         recipt_proxy.transaction_index, recipt_geth.transaction_index,
         "Transaction indexes should match between providers: Proxy == Geth"
     );
-    assert_eq!(
-        recipt_proxy.logs_bloom, recipt_geth.logs_bloom,
-        "Logs bloom should match between providers: Proxy == Geth"
-    );
-    assert_eq!(
-        recipt_proxy.logs, recipt_geth.logs,
-        "Logs should match between providers: Proxy == Geth"
+    println!("[ {:.2}s ] - Check recipt", start.elapsed().as_secs_f64());
+}
+
+/// Compare all the fields of the proxy and geth receipts with the policies of the comparator,
+/// the opt-in alternative of [check_recipt]
+#[allow(dead_code)]
+pub async fn check_recipt_with(recipt: &TransactionReceipt, comparator: &ReceiptComparator) {
+    let start = Instant::now();
+    let recipt_proxy = get_provider(get_proxy_url())
+        .get_transaction_receipt(recipt.transaction_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    let recipt_geth = get_provider(get_geth_url())
+        .get_transaction_receipt(recipt.transaction_hash)
+        .await
+        .unwrap()
+        .unwrap();
+    let diffs = comparator.compare(&recipt_proxy, &recipt_geth);
+    assert!(
+        diffs.is_empty(),
+        "Receipts should match between providers: Proxy == Geth: {:#?}",
+        diffs
     );
     println!("[ {:.2}s ] - Check recipt", start.elapsed().as_secs_f64());
 }
@@ -674,19 +626,23 @@ pub async fn transfer(
     Ok(())
 }
 
+/// Solana RPC of the local stack, reached from the host or from the docker network
 #[allow(dead_code)]
-pub fn solana_balance(address: Pubkey) -> U256 {
-    let rpc_url = if get_proxy_url().contains("localhost") || get_geth_url().contains("localhost") {
+pub fn solana_rpc_url() -> &'static str {
+    if get_proxy_url().contains("localhost") || get_geth_url().contains("localhost") {
         "http://localhost:8899"
     } else {
         "http://solana:8899"
-    };
+    }
+}
 
+#[allow(dead_code)]
+pub fn solana_balance(address: Pubkey) -> U256 {
     let output = Command::new("/opt/bin/solana")
         .arg("balance")
         .arg(address.to_string())
         .arg("-u")
-        .arg(rpc_url)
+        .arg(solana_rpc_url())
         .output()
         .expect("Failed to execute solana balance command");
 
@@ -731,11 +687,34 @@ pub async fn airdrop_to_address(
     );
 
     let pending_tx = client.send_transaction(tx, None).await?;
-    let _receipt = pending_tx
+    wait_for_confirmation(pending_tx).await
+}
+
+/// Error of the transaction removed from the mempool before the inclusion
+#[allow(dead_code)]
+pub const DROPPED: &str = "[ Info: ] - Transaction dropped from mempool";
+
+/// Wait for the transaction of [airdrop_to_address]: only the failed polling is an error,
+/// the transaction dropped from the mempool is resolved without the receipt and it is not an error
+#[allow(dead_code)]
+pub async fn wait_for_confirmation(
+    pending_tx: PendingTransaction<'_, Http>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let _receipt = pending_tx.confirmations(1usize).await.map_err(|_| DROPPED)?;
+    Ok(())
+}
+
+/// Wait for the receipt. Unlike [wait_for_confirmation], the transaction dropped from the mempool
+/// is resolved without the receipt and it is an error as well as the failed polling
+#[allow(dead_code)]
+pub async fn wait_for_inclusion(
+    pending_tx: PendingTransaction<'_, Http>,
+) -> Result<TransactionReceipt, Box<dyn std::error::Error>> {
+    pending_tx
         .confirmations(1usize)
         .await
-        .map_err(|_| "[ Info: ] - Transaction dropped from mempool")?;
-    Ok(())
+        .map_err(|_| DROPPED)?
+        .ok_or_else(|| DROPPED.into())
 }
 
 #[allow(dead_code)]
@@ -770,7 +749,7 @@ pub async fn transfer_tx(
         let receipt = pending_tx
             .confirmations(1usize)
             .await?
-            .ok_or("[ Info: ] - Transaction dropped from mempool")?;
+            .ok_or(DROPPED)?;
         println!(
             "[ {:.2}s ] - Tx send: {}",
             start.elapsed().as_secs_f64(),
@@ -781,4 +760,57 @@ pub async fn transfer_tx(
         check_state(vec![sender.address(), receiver_address]).await;
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Deploy the raw init code, the gas limit is estimated by the provider if not set.
+/// Returns the would-be contract address and the receipt
+#[allow(dead_code)]
+pub async fn deploy_bytecode(
+    bytecode: Bytes,
+    provider_name: &str,
+    sender: &Wallet<SigningKey>,
+    gas: Option<U256>,
+) -> Result<(H160, TransactionReceipt), Box<dyn std::error::Error>> {
+    let start = Instant::now();
+    let provider = return_current_provider(provider_name);
+    let nonce = provider.get_transaction_count(sender.address(), None).await?;
+    let address = ethers::utils::get_contract_address(sender.address(), nonce);
+    let client = SignerMiddleware::new(provider, sender.clone());
+
+    let mut tx = TransactionRequest::new().data(bytecode).nonce(nonce);
+    if let Some(gas) = gas {
+        tx = tx.gas(gas);
+    }
+    let receipt = client
+        .send_transaction(tx, None)
+        .await?
+        .confirmations(1usize)
+        .await?
+        .ok_or(DROPPED)?;
+    println!(
+        "[ {:.2}s ] - Bytecode deployment: {:?}, status {:?}",
+        start.elapsed().as_secs_f64(),
+        address,
+        receipt.status
+    );
+    Ok((address, receipt))
+}
+
+/// eth_call at the latest block with the stateOverride and the block overrides parameters
+#[allow(dead_code)]
+pub async fn call_with_overrides(
+    provider: &Provider<Http>,
+    tx: &TransactionRequest,
+    state: &StateOverride,
+    block: Option<&BlockOverrides>,
+) -> Result<Bytes, ProviderError> {
+    let mut params = vec![
+        serde_json::to_value(tx)?,
+        serde_json::json!("latest"),
+        serde_json::to_value(state)?,
+    ];
+    if let Some(block) = block {
+        params.push(serde_json::to_value(block)?);
+    }
+    provider.request("eth_call", params).await
+}
@@ -0,0 +1,158 @@
+use ethers_core::k256::ecdsa::SigningKey;
+use ethers_core::types::{transaction::eip2718::TypedTransaction, Signature, U256};
+use ethers_signers::{Signer, Wallet};
+use super::{client::Client, tx::{do_rlp, do_tx_base}};
+
+/// Maximum size of the transaction accepted by the mempool, 128 KB as in geth
+#[allow(dead_code)]
+pub const MAX_TX_SIZE: usize = 128 * 1024;
+
+/// Mutations of the valid transaction making it invalid
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Invalid {
+    /// signed for another chain
+    WrongChainId,
+    /// r of the signature is zero
+    BadSignature,
+    /// nonce of the already executed transaction
+    NonceTooLow,
+    /// the maximal nonce, the next nonce overflows
+    NonceTooHigh,
+    /// gas limit below the intrinsic gas of the transfer
+    GasBelowIntrinsic,
+    /// value above the balance of the sender
+    ValueAboveBalance,
+    /// max_fee_per_gas below the base fee, eip-1559 transaction
+    FeeCapBelowBaseFee,
+    /// transaction above MAX_TX_SIZE
+    OversizedCalldata,
+    /// legacy transaction signed without the chain id, pre EIP-155
+    UnprotectedLegacy,
+}
+
+/// Endpoint the invalid transaction is sent to
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    /// send_transaction of the sdk, the error of the rome-evm program or of the sdk
+    Sdk,
+    /// eth_sendRawTransaction of op-geth
+    Geth,
+    /// eth_sendRawTransaction of the proxy
+    Proxy,
+}
+
+#[allow(dead_code)]
+impl Invalid {
+    /// The rejection error returned by the target.
+    /// None if the target does not reject the transaction: the rome-evm program has no base fee,
+    /// the geth pool keeps the transaction with the fee cap below the base fee pending
+    pub fn error(&self, target: Target) -> Option<&'static str> {
+        let error = match (self, target) {
+            (Invalid::FeeCapBelowBaseFee, Target::Sdk | Target::Geth) => return None,
+            (Invalid::FeeCapBelowBaseFee, Target::Proxy) => "max fee per gas less than block base fee",
+
+            (Invalid::WrongChainId, Target::Geth) => "invalid chain id for signer",
+            (Invalid::BadSignature, Target::Geth) => "invalid transaction v, r, s values",
+            (Invalid::NonceTooLow, Target::Geth) => "nonce too low",
+            (Invalid::NonceTooHigh, Target::Geth) => "nonce has max value",
+            (Invalid::GasBelowIntrinsic, Target::Geth) => "intrinsic gas too low",
+            (Invalid::ValueAboveBalance, Target::Geth) => "insufficient funds for gas * price + value",
+            (Invalid::OversizedCalldata, Target::Geth) => "oversized data",
+            (Invalid::UnprotectedLegacy, Target::Geth) => "only replay-protected (EIP-155) transactions allowed over RPC",
+
+            // the proxy and the sdk return the errors of the rome-evm emulator
+            (Invalid::WrongChainId, _) => "InvalidChainId",
+            (Invalid::BadSignature, _) => "InvalidSignature",
+            (Invalid::NonceTooLow, _) => "InvalidNonce",
+            (Invalid::NonceTooHigh, _) => "InvalidNonce",
+            (Invalid::GasBelowIntrinsic, _) => "IntrinsicGas",
+            (Invalid::ValueAboveBalance, _) => "InsufficientFunds",
+            (Invalid::OversizedCalldata, _) => "TooLargeTx",
+            (Invalid::UnprotectedLegacy, _) => "InvalidChainId",
+        };
+        Some(error)
+    }
+
+    /// Type of the mutated transaction
+    pub fn tx_type(&self) -> u8 {
+        match self {
+            Invalid::FeeCapBelowBaseFee => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// RLP of the transfer from the wallet to itself mutated to be invalid.
+/// The transfer is built by [do_tx_base] with the current nonce of the wallet.
+/// The rome-evm program has no base fee, FeeCapBelowBaseFee is not built
+#[allow(dead_code)]
+pub fn invalid_rlp(client: &Client, wallet: &Wallet<SigningKey>, invalid: Invalid) -> Vec<u8> {
+    let nonce = client.transaction_count(wallet.address()).unwrap().as_u64();
+    let tx = do_tx_base(client, Some(wallet.address()), vec![], wallet, 0.into(), invalid.tx_type(), nonce);
+
+    mutate(tx, wallet, invalid, client.get_balance(wallet.address()).unwrap(), None)
+}
+
+/// Mutate the valid signable transaction and sign it by [do_rlp].
+/// The nonce, the chain id and the fees of the transaction must be set, balance is the balance of the wallet,
+/// base_fee is the base fee of the latest block, required by FeeCapBelowBaseFee
+#[allow(dead_code)]
+pub fn mutate(
+    mut tx: TypedTransaction,
+    wallet: &Wallet<SigningKey>,
+    invalid: Invalid,
+    balance: U256,
+    base_fee: Option<U256>,
+) -> Vec<u8> {
+    let nonce = tx.nonce().expect("nonce is not set").as_u64();
+    let chain_id = tx.chain_id().expect("chain id is not set").as_u64();
+
+    match invalid {
+        Invalid::WrongChainId => {
+            tx.set_chain_id(chain_id + 1);
+        },
+        Invalid::BadSignature => {
+            let signature = wallet.sign_transaction_sync(&tx).unwrap();
+            return tx.rlp_signed(&Signature { r: U256::zero(), ..signature }).to_vec();
+        },
+        Invalid::NonceTooLow => {
+            assert!(nonce > 0, "wallet has no executed transactions");
+            tx.set_nonce(nonce - 1);
+        },
+        Invalid::NonceTooHigh => {
+            tx.set_nonce(u64::MAX);
+        },
+        Invalid::GasBelowIntrinsic => {
+            tx.set_gas(20_999);
+        },
+        Invalid::ValueAboveBalance => {
+            tx.set_value(balance + 1);
+        },
+        Invalid::FeeCapBelowBaseFee => {
+            let TypedTransaction::Eip1559(eip1559) = &mut tx else { panic!("eip-1559 transaction expected") };
+            let base_fee = base_fee.expect("base fee is required");
+            assert!(!base_fee.is_zero(), "base fee is zero");
+            let fee_cap = base_fee - 1;
+            let tip = eip1559.max_priority_fee_per_gas.unwrap_or_default().min(fee_cap);
+            eip1559.max_fee_per_gas = Some(fee_cap);
+            eip1559.max_priority_fee_per_gas = Some(tip);
+        },
+        Invalid::OversizedCalldata => {
+            // 16 gas per non-zero byte
+            let data = vec![1u8; MAX_TX_SIZE];
+            tx.set_gas(U256::from(21_000 + 16 * data.len()));
+            tx.set_data(data.into());
+        },
+        Invalid::UnprotectedLegacy => {
+            let TypedTransaction::Legacy(legacy) = &mut tx else { panic!("legacy transaction expected") };
+            legacy.chain_id = None;
+            // v = 27 / 28, the chain id is not included in the sighash
+            let signature = wallet.sign_hash(tx.sighash()).unwrap();
+            return tx.rlp_signed(&signature).to_vec();
+        },
+    }
+
+    do_rlp(&tx, wallet)
+}
@@ -1,17 +1,30 @@
 mod shared;
 
 use crate::shared::utils::{
-    check_recipt, check_state, deploy_contract, airdrop_to_address,
+    check_recipt, check_recipt_with, check_state, check_storage, deploy_contract, deploy_bytecode, airdrop_to_address,
+    call_with_overrides, get_bin, get_chain_id, prepare_tx, return_current_provider, DROPPED,
 };
 use shared::{
+    differential::{compare, execute, regressions, REGRESSIONS},
+    history::History,
+    opcodes::{OpcodeMatrix, GROUPS},
+    overrides::{BlockOverrides, StateOverride},
+    precompile::{load_vectors, probe_gas, vector},
+    receipt::{self, FieldPolicy, ReceiptComparator},
+    tx::calc_create2_address,
+    bytecode::{init_code, payload, runtime_code, MAX_CODE_SIZE, MAX_INITCODE_SIZE, RETURN_VALUE},
     utils::get_random_wallet,
+    validation::{mutate, Invalid, Target},
 };
 use std::time::Instant;
 use {
-    ethers_core::types::{U256},
+    ethers::{abi::{AbiDecode, Token}, providers::Middleware, utils::get_contract_address},
+    ethers::middleware::SignerMiddleware,
+    ethers_core::types::{BlockNumber, Bytes, TransactionReceipt, TransactionRequest, H160, H256, U256, U64},
     ethers_signers::Signer,
     rstest::*,
     serial_test::serial,
+    serde_json::json,
 
 };
 
@@ -104,3 +117,748 @@ async fn check_contract(
   );
   Ok(())
 }
+
+/// Generated contracts of the given size are deployed through both providers.
+/// Proxy and geth make the same decision: the deployment above the EIP-170 or EIP-3860 limits
+/// is rejected by the provider or mined with the failed status, the others are deployed.
+/// The deployed contract is called with the payload of call_size bytes
+#[rstest(
+  code_size,
+  ctor_size,
+  call_size,
+  deployed,
+
+  case::small(1_000, 0, 1_000, true),
+  case::near_limit(MAX_CODE_SIZE - 1, 0, 10_000, true),
+  case::limit(MAX_CODE_SIZE, 0, 0, true),
+  case::above_limit(MAX_CODE_SIZE + 1, 0, 0, false),
+  case::large_ctor(1_000, 40_000, 0, true),
+  case::initcode_above_limit(MAX_CODE_SIZE, MAX_INITCODE_SIZE - MAX_CODE_SIZE, 0, false),
+)]
+#[serial]
+async fn large_contract(
+  code_size: usize,
+  ctor_size: usize,
+  call_size: usize,
+  deployed: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let runtime = runtime_code(code_size);
+  let bytecode = init_code(&runtime, &payload(ctor_size));
+  println!("[ Info: ] - Init code {} bytes, runtime code {} bytes", bytecode.len(), runtime.len());
+
+  let mut decisions = vec![];
+  for provider_name in ["geth", "proxy"] {
+    let sender = get_random_wallet();
+    let _ = airdrop_to_address(sender.address(), U256::exp10(19), provider_name).await?; // fund sender
+    let provider = return_current_provider(provider_name);
+
+    // the gas limit is set for the failing deployments, estimate_gas fails for them
+    let gas = (!deployed).then(|| U256::from(30_000_000));
+    let result = deploy_bytecode(bytecode.clone().into(), provider_name, &sender, gas).await;
+
+    let accepted = match result {
+      Ok((address, receipt)) => {
+        check_recipt(&receipt).await;
+        if receipt.status == Some(U64::one()) {
+          assert_eq!(receipt.contract_address, Some(address));
+          assert_eq!(provider.get_code(address, None).await?.to_vec(), runtime);
+          check_state(vec![sender.address(), address]).await;
+
+          // call with the large payload
+          let tx = TransactionRequest::new().to(address).data(payload(call_size));
+          let result = provider.call(&tx.from(sender.address()).into(), None).await?;
+          assert_eq!(U256::from_big_endian(&result), U256::from(RETURN_VALUE));
+          true
+        } else {
+          assert!(provider.get_code(address, None).await?.is_empty());
+          check_state(vec![sender.address(), address]).await;
+          false
+        }
+      },
+      Err(e) => {
+        println!("[ Info: ] - Deployment rejected by {}: {}", provider_name, e);
+        check_state(vec![sender.address()]).await;
+        false
+      },
+    };
+    println!("[ Info: ] - {} deployment accepted: {}", provider_name, accepted);
+    decisions.push(accepted);
+  }
+
+  assert_eq!(decisions[0], decisions[1], "proxy and geth made different decisions: geth {}, proxy {}", decisions[0], decisions[1]);
+  assert_eq!(decisions[0], deployed);
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// CREATE2 children of the factory: the address matches calc_create2_address,
+/// proxy and geth return the same code. The child destroyed in the creating transaction is removed (EIP-6780)
+#[rstest(
+  provider_name,
+  method,
+  removed,
+
+  case::deploy_geth("geth", "deploy", false),
+  case::deploy_proxy("proxy", "deploy", false),
+  case::deploy_and_destruct_geth("geth", "deploy_and_destruct", true),
+  case::deploy_and_destruct_proxy("proxy", "deploy_and_destruct", true),
+)]
+#[serial]
+async fn create2(
+  provider_name: &str,
+  method: &str,
+  removed: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), provider_name).await?; // fund sender
+  let provider = return_current_provider(provider_name);
+
+  let (factory, recipt) = deploy_contract("Create2Factory", provider_name, &sender).await.unwrap();
+  check_recipt(&recipt).await;
+
+  for salt in [[1u8; 32], [2u8; 32]] {
+    let expected = calc_create2_address(&factory.address(), salt, &get_bin("Create2Child"));
+    let calc: H160 = factory.method::<_, H160>("calc", salt)?.call().await?;
+    assert_eq!(calc, expected);
+
+    let tx_recipt = factory
+        .method::<_, ()>(method, salt)?
+        .send()
+        .await?
+        .await?;
+    println!("[ Info: ] - Checking recipt for {}", method);
+    check_recipt(&tx_recipt.unwrap()).await;
+
+    let last: H160 = factory.method::<_, H160>("last", ())?.call().await?;
+    assert_eq!(last, expected);
+    assert_eq!(provider.get_code(expected, None).await?.is_empty(), removed);
+    check_state(vec![factory.address(), expected]).await;
+  }
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// Cancun EIP-6780, see evm_selfdestruct: the child destroyed in the creating transaction is removed,
+/// the child destroyed later keeps the code, storage and nonce. Proxy and geth state must match
+#[rstest(
+  provider_name,
+  case::selfdestruct_geth("geth"),
+  case::selfdestruct_proxy("proxy"),
+)]
+#[serial]
+async fn selfdestruct(provider_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), provider_name).await?; // fund sender
+  let provider = return_current_provider(provider_name);
+  let (geth, proxy) = (return_current_provider("geth"), return_current_provider("proxy"));
+  let value = U256::exp10(15);
+
+  let (caller, recipt) = deploy_contract("DestructCaller", provider_name, &sender).await.unwrap();
+  check_recipt(&recipt).await;
+
+  for method in ["deploy_and_destruct", "deploy", "destruct_first", "check"] {
+    let value = if method.starts_with("deploy") { value } else { U256::zero() };
+    let tx_recipt = caller
+        .method::<_, ()>(method, ())?
+        .value(value)
+        .send()
+        .await?
+        .await?;
+    println!("[ Info: ] - Checking recipt for {}", method);
+    check_recipt(&tx_recipt.unwrap()).await;
+  }
+
+  let addr1: H160 = caller.method::<_, H160>("addr1", ())?.call().await?;
+  let addr2: H160 = caller.method::<_, H160>("addr2", ())?.call().await?;
+
+  // destroyed in the creating transaction
+  assert!(provider.get_code(addr2, None).await?.is_empty());
+  assert_eq!(provider.get_balance(addr2, None).await?, U256::zero());
+  assert_eq!(provider.get_transaction_count(addr2, None).await?, U256::zero());
+  check_storage(provider.clone(), &proxy, &geth, addr2, Some(vec![0]), Some(vec![0])).await;
+
+  // destroyed in a later transaction
+  assert!(!provider.get_code(addr1, None).await?.is_empty());
+  assert_eq!(provider.get_balance(addr1, None).await?, U256::zero());
+  assert_eq!(provider.get_transaction_count(addr1, None).await?, U256::one());
+  check_storage(provider.clone(), &proxy, &geth, addr1, Some(vec![0]), Some(vec![1])).await;
+
+  // the balances of both children are sent to the caller
+  assert_eq!(provider.get_balance(caller.address(), None).await?, value * 2);
+  check_state(vec![caller.address(), addr1, addr2]).await;
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// EIP-1153, see evm_transient_storage. The Loaded events (step, value) are checked in the receipts,
+/// the receipts of proxy and geth must match
+#[rstest(
+  provider_name,
+  method,
+  logs,
+  case::set_geth("geth", "set", vec![(0, 5)]),
+  case::set_proxy("proxy", "set", vec![(0, 5)]),
+  case::nested_geth("geth", "nested", vec![(1, 5), (2, 6), (3, 6)]),
+  case::nested_proxy("proxy", "nested", vec![(1, 5), (2, 6), (3, 6)]),
+)]
+#[serial]
+async fn transient_storage(
+  provider_name: &str,
+  method: &str,
+  logs: Vec<(u64, u64)>,
+) -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), provider_name).await?; // fund sender
+
+  let (contract, recipt) = deploy_contract("TransientProbe", provider_name, &sender).await.unwrap();
+  check_recipt(&recipt).await;
+
+  let mut loaded = vec![];
+  for (method, arg) in [(method, Some(U256::from(5))), ("load", None)] {
+    let call = match arg {
+      Some(arg) => contract.method::<_, U256>(method, arg)?,
+      None => contract.method::<_, U256>(method, ())?,
+    };
+    let tx_recipt = call.send().await?.await?.unwrap();
+    println!("[ Info: ] - Checking recipt for {}", method);
+    check_recipt(&tx_recipt).await;
+
+    let events = tx_recipt
+        .logs
+        .iter()
+        .map(|log| <(U256, U256)>::decode(&log.data).map(|(step, value)| (step.as_u64(), value.as_u64())))
+        .collect::<Result<Vec<_>, _>>()?;
+    loaded.push(events);
+  }
+
+  // the value is cleared at the end of the transaction
+  assert_eq!(loaded, vec![logs, vec![(0, 0)]]);
+  let value: U256 = contract.method::<_, U256>("get", ())?.call().await?;
+  assert_eq!(value, U256::zero());
+  check_state(vec![contract.address()]).await;
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// Precompile vectors of shared::precompile: the output and the gas of the direct call and of PrecompileProbe,
+/// the receipts of PrecompileProbe calls must match between proxy and geth
+#[rstest(
+  provider_name,
+  name,
+  case::ecrecover_geth("geth", "ecrecover"),
+  case::ecrecover_proxy("proxy", "ecrecover"),
+  case::ecrecover_invalid_v_geth("geth", "ecrecover_invalid_v"),
+  case::ecrecover_invalid_v_proxy("proxy", "ecrecover_invalid_v"),
+  case::sha256_geth("geth", "sha256"),
+  case::sha256_proxy("proxy", "sha256"),
+  case::ripemd160_geth("geth", "ripemd160"),
+  case::ripemd160_proxy("proxy", "ripemd160"),
+  case::identity_geth("geth", "identity"),
+  case::identity_proxy("proxy", "identity"),
+  case::modexp_geth("geth", "modexp"),
+  case::modexp_proxy("proxy", "modexp"),
+  case::modexp_small_geth("geth", "modexp_small"),
+  case::modexp_small_proxy("proxy", "modexp_small"),
+  case::bn256_add_geth("geth", "bn256_add"),
+  case::bn256_add_proxy("proxy", "bn256_add"),
+  case::bn256_add_not_on_curve_geth("geth", "bn256_add_not_on_curve"),
+  case::bn256_add_not_on_curve_proxy("proxy", "bn256_add_not_on_curve"),
+  case::bn256_mul_geth("geth", "bn256_mul"),
+  case::bn256_mul_proxy("proxy", "bn256_mul"),
+  case::bn256_pairing_empty_geth("geth", "bn256_pairing_empty"),
+  case::bn256_pairing_empty_proxy("proxy", "bn256_pairing_empty"),
+  case::blake2f_geth("geth", "blake2f"),
+  case::blake2f_proxy("proxy", "blake2f"),
+  case::point_evaluation_geth("geth", "point_evaluation"),
+  case::point_evaluation_proxy("proxy", "point_evaluation"),
+  case::point_evaluation_invalid_length_geth("geth", "point_evaluation_invalid_length"),
+  case::point_evaluation_invalid_length_proxy("proxy", "point_evaluation_invalid_length"),
+)]
+#[serial]
+async fn precompile(provider_name: &str, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+  let vector = vector(name);
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), provider_name).await?; // fund sender
+  let provider = return_current_provider(provider_name);
+
+  // direct call
+  let tx = TransactionRequest::new()
+      .from(sender.address())
+      .to(vector.address())
+      .data(vector.input.clone());
+  let result = provider.call(&tx.clone().into(), None).await;
+  println!("[ Info: ] - {}: {:?}", name, result);
+
+  if let Some(output) = &vector.output {
+    assert_eq!(&result?.to_vec(), output);
+    assert_eq!(provider.estimate_gas(&tx.clone().into(), None).await?, vector.tx_gas().into());
+
+    let client = SignerMiddleware::new(provider.clone(), sender.clone());
+    let receipt = client
+        .send_transaction(tx.gas(vector.tx_gas()), None)
+        .await?
+        .confirmations(1usize)
+        .await?
+        .ok_or(DROPPED)?;
+    assert_eq!(receipt.status, Some(U64::one()));
+    assert_eq!(receipt.gas_used, Some(vector.tx_gas().into()));
+    check_recipt(&receipt).await;
+  } else {
+    assert!(result.is_err());
+  }
+
+  // call through the contract, the gas measured by the probe matches the gas schedule
+  let (contract, recipt) = deploy_contract("PrecompileProbe", provider_name, &sender).await.unwrap();
+  check_recipt(&recipt).await;
+  let (_, _, identity_empty) = contract
+      .method::<_, (bool, Bytes, U256)>("probe", (H160::from_low_u64_be(4), Bytes::new()))?
+      .call()
+      .await?;
+  let call = contract.method::<_, (bool, Bytes, U256)>("probe", (vector.address(), Bytes::from(vector.input.clone())))?;
+  let (success, output, used) = call.call().await?;
+  assert_eq!(success, vector.output.is_some());
+  assert_eq!(output.to_vec(), vector.output.clone().unwrap_or_default());
+  if vector.output.is_some() {
+    assert_eq!(probe_gas(used, identity_empty), vector.gas);
+  }
+
+  let tx_recipt = call.send().await?.await?.unwrap();
+  check_recipt(&tx_recipt).await;
+  check_state(vec![sender.address(), contract.address()]).await;
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// Vectors of the go-ethereum test data: proxy and geth return the expected output
+/// and estimate the gas of the vector schedule for the direct calls
+#[rstest(
+  file,
+  precompile,
+  case::ecrecover("ecRecover.json", 1),
+  case::modexp("modexp_eip2565.json", 5),
+  case::bn256_add("bn256Add.json", 6),
+  case::bn256_mul("bn256ScalarMul.json", 7),
+  case::bn256_pairing("bn256Pairing.json", 8),
+  case::blake2f("blake2F.json", 9),
+  case::point_evaluation("pointEvaluation.json", 10),
+)]
+#[serial]
+async fn precompile_vectors(file: &str, precompile: u8) -> Result<(), Box<dyn std::error::Error>> {
+  let sender = get_random_wallet();
+
+  for vector in load_vectors(file, precompile) {
+    for provider_name in ["geth", "proxy"] {
+      let provider = return_current_provider(provider_name);
+      let tx = TransactionRequest::new()
+          .from(sender.address())
+          .to(vector.address())
+          .data(vector.input.clone());
+      let output = provider.call(&tx.clone().into(), None).await?;
+      assert_eq!(Some(output.to_vec()), vector.output, "{}: output of {}", provider_name, vector.name);
+
+      let estimate = provider.estimate_gas(&tx.into(), None).await?;
+      assert_eq!(estimate, vector.tx_gas().into(), "{}: gas of {}", provider_name, vector.name);
+    }
+  }
+  Ok(())
+}
+
+/// Opcode groups of opcodes.sol are run through both providers. Every contract writes the results
+/// to the storage slots, the slots of proxy and geth are compared and written to the opcode matrix.
+/// Only the opcodes of shared::opcodes::MUST_MATCH fail the test
+#[rstest]
+#[serial]
+async fn opcodes() -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+  let mut matrix = OpcodeMatrix::default();
+
+  for (name, names) in GROUPS {
+    for provider_name in ["geth", "proxy"] {
+      let sender = get_random_wallet();
+      let _ = airdrop_to_address(sender.address(), U256::exp10(18), provider_name).await?; // fund sender
+      let provider = return_current_provider(provider_name);
+
+      let (contract, recipt) = deploy_contract(name, provider_name, &sender).await.unwrap();
+      check_recipt(&recipt).await;
+
+      let call = if contract.abi().function("run")?.inputs.is_empty() {
+        contract.method::<_, ()>("run", ())?
+      } else {
+        contract.method::<_, ()>("run", get_random_wallet().address())?.value(1_000)
+      };
+      let tx_recipt = call.send().await?.await?.unwrap();
+      check_recipt(&tx_recipt).await;
+      check_state(vec![contract.address()]).await;
+
+      // values known to the test
+      let value = |slot: u64| provider.get_storage_at(contract.address(), H256::from_low_u64_be(slot), None);
+      if names[0] == "NUMBER" {
+        assert_eq!(U256::from_big_endian(value(0).await?.as_bytes()), tx_recipt.block_number.unwrap().as_u64().into());
+        assert_eq!(U256::from_big_endian(value(4).await?.as_bytes()), provider.get_chainid().await?);
+      }
+      if names[0] == "ORIGIN" {
+        assert_eq!(H160::from(value(0).await?), sender.address());
+        assert_eq!(H160::from(value(2).await?), sender.address());
+      }
+
+      matrix.compare(name, provider_name, contract.address(), names).await;
+    }
+  }
+
+  matrix.write();
+  let failures = matrix.failures();
+  assert!(failures.is_empty(), "opcode semantics differ from geth: {:#?}", failures);
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// The expected state of the contract is derived from every call, then queried at the block numbers,
+/// the block hashes (EIP-1898) and the block tags. Proxy and geth must return the expected states
+#[rstest]
+#[serial]
+async fn historical_state() -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), "proxy").await?; // fund sender
+
+  let (contract, recipt) = deploy_contract("TouchStorage", "proxy", &sender).await.unwrap();
+  check_recipt(&recipt).await;
+  // all the fields of the receipt, the contract address and the L2 fields included
+  check_recipt_with(&recipt, &ReceiptComparator::op_stack()).await;
+
+  // slot 0: value, slot 1: length of vec. The code is not changed by the scenario, it is taken from geth
+  let code = return_current_provider("geth").get_code(contract.address(), None).await?;
+  let mut history = History::new(contract.address(), &[0, 1], code);
+  history.record(&recipt, |_| ());
+
+  let calls = [("set_value", 1u64), ("push_vec", 2), ("set_value", 3), ("deploy", 0)];
+  for (method, arg) in calls {
+    let call = if method == "deploy" {
+      contract.method::<_, ()>(method, ())?
+    } else {
+      contract.method::<_, ()>(method, U256::from(arg))?
+    };
+    let tx_recipt = call.send().await?.await?.unwrap();
+    check_recipt(&tx_recipt).await;
+    check_recipt_with(&tx_recipt, &ReceiptComparator::op_stack()).await;
+    history.record(&tx_recipt, |state| match method {
+      "set_value" => state.storage[0] = H256::from_low_u64_be(arg),
+      "push_vec" => state.storage[1] = H256::from_low_u64_be(state.storage[1].to_low_u64_be() + 1),
+      // the contract created by the contract increments its nonce
+      _ => state.nonce += U256::one(),
+    });
+  }
+  check_state(vec![contract.address()]).await;
+
+  let mut diffs = vec![];
+  for name in ["proxy", "geth"] {
+    diffs.extend(history.check(&return_current_provider(name), name).await);
+  }
+  assert!(diffs.is_empty(), "historical state differs from the recorded one: {:#?}", diffs);
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// eth_call with the stateOverride and the block overrides parameters.
+/// The proxy must apply the overrides the same way as geth
+#[rstest(
+  override_name,
+  case::balance("balance"),
+  case::nonce("nonce"),
+  case::code("code"),
+  case::state("state"),
+  case::state_diff("state_diff"),
+  case::block("block"),
+)]
+#[serial]
+async fn call_overrides(override_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), "proxy").await?; // fund sender
+  let (geth, proxy) = (return_current_provider("geth"), return_current_provider("proxy"));
+
+  let (contract, recipt) = deploy_contract("OverrideProbe", "proxy", &sender).await.unwrap();
+  check_recipt(&recipt).await;
+  let probe = contract.address();
+  let eoa = get_random_wallet().address();
+  let slot = |i: u64| H256::from_low_u64_be(i);
+  let word = |x: U256| Bytes::from(ethers::abi::encode(&[Token::Uint(x)]));
+
+  // (calls with the expected results, state override, block overrides)
+  let (calls, state, block) = match override_name {
+    "balance" => (
+      vec![(probe, contract.encode("balance_of", eoa)?, word(U256::exp10(20)))],
+      StateOverride::default().balance(eoa, U256::exp10(20)),
+      None,
+    ),
+    "nonce" => (
+      vec![(probe, contract.encode("next_address", ())?, word(U256::from(get_contract_address(probe, 5u64).as_bytes())))],
+      StateOverride::default().nonce(probe, 5),
+      None,
+    ),
+    "code" => (
+      vec![
+        (probe, contract.encode("code_size", eoa)?, word(64.into())),
+        (eoa, Bytes::new(), word(RETURN_VALUE.into())),
+      ],
+      StateOverride::default().code(eoa, runtime_code(64).into()),
+      None,
+    ),
+    // the storage is replaced, other slots are cleared
+    "state" => (
+      vec![
+        (probe, contract.encode("value", ())?, word(7.into())),
+        (probe, contract.encode("other", ())?, word(0.into())),
+      ],
+      StateOverride::default().state(probe, slot(0), slot(7)),
+      None,
+    ),
+    // only the given slot is replaced
+    "state_diff" => (
+      vec![
+        (probe, contract.encode("value", ())?, word(7.into())),
+        (probe, contract.encode("other", ())?, word(2.into())),
+      ],
+      StateOverride::default().state_diff(probe, slot(0), slot(7)),
+      None,
+    ),
+    "block" => {
+      let number = U256::from(proxy.get_block_number().await?.as_u64() + 100);
+      let block = BlockOverrides {
+        number: Some(number),
+        time: Some(4_000_000_000u64.into()),
+        gas_limit: Some(50_000_000u64.into()),
+        fee_recipient: Some(eoa),
+        prev_randao: Some(slot(42)),
+        base_fee_per_gas: Some(0.into()),
+      };
+      let expected = ethers::abi::encode(&[
+        Token::Uint(number),
+        Token::Uint(4_000_000_000u64.into()),
+        Token::Uint(50_000_000u64.into()),
+        Token::Address(eoa),
+        Token::Uint(42.into()),
+        Token::Uint(0.into()),
+      ]);
+      (
+        vec![(probe, contract.encode("block_context", ())?, expected.into())],
+        StateOverride::default(),
+        Some(block),
+      )
+    },
+    _ => unimplemented!("Override not supported"),
+  };
+
+  for (to, data, expected) in calls {
+    let tx = TransactionRequest::new().from(sender.address()).to(to).data(data);
+    let proxy_result = call_with_overrides(&proxy, &tx, &state, block.as_ref()).await?;
+    let geth_result = call_with_overrides(&geth, &tx, &state, block.as_ref()).await?;
+    println!("[ Override ] - {}: proxy {}, geth {}", override_name, proxy_result, geth_result);
+
+    assert_eq!(geth_result, expected, "geth result is not expected");
+    assert_eq!(proxy_result, geth_result, "eth_call results should match between providers: Proxy == Geth");
+  }
+
+  // overrides do not change the state
+  check_storage(proxy.clone(), &proxy, &geth, probe, Some(vec![0, 1]), Some(vec![1, 2])).await;
+  check_state(vec![probe, eoa]).await;
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// The invalid transactions are rejected with the error of the provider, see evm_invalid_tx.
+/// The geth pool keeps the transaction with the fee cap below the base fee pending, the case is proxy only
+#[rstest(
+  invalid,
+  provider_name,
+  case::wrong_chain_id_geth(Invalid::WrongChainId, "geth"),
+  case::wrong_chain_id_proxy(Invalid::WrongChainId, "proxy"),
+  case::bad_signature_geth(Invalid::BadSignature, "geth"),
+  case::bad_signature_proxy(Invalid::BadSignature, "proxy"),
+  case::nonce_too_low_geth(Invalid::NonceTooLow, "geth"),
+  case::nonce_too_low_proxy(Invalid::NonceTooLow, "proxy"),
+  case::nonce_too_high_geth(Invalid::NonceTooHigh, "geth"),
+  case::nonce_too_high_proxy(Invalid::NonceTooHigh, "proxy"),
+  case::gas_below_intrinsic_geth(Invalid::GasBelowIntrinsic, "geth"),
+  case::gas_below_intrinsic_proxy(Invalid::GasBelowIntrinsic, "proxy"),
+  case::value_above_balance_geth(Invalid::ValueAboveBalance, "geth"),
+  case::value_above_balance_proxy(Invalid::ValueAboveBalance, "proxy"),
+  case::fee_cap_below_base_fee_proxy(Invalid::FeeCapBelowBaseFee, "proxy"),
+  case::oversized_calldata_geth(Invalid::OversizedCalldata, "geth"),
+  case::oversized_calldata_proxy(Invalid::OversizedCalldata, "proxy"),
+  case::unprotected_legacy_geth(Invalid::UnprotectedLegacy, "geth"),
+  case::unprotected_legacy_proxy(Invalid::UnprotectedLegacy, "proxy"),
+)]
+#[serial]
+async fn invalid_tx(invalid: Invalid, provider_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), provider_name).await?; // fund sender
+  let provider = return_current_provider(provider_name);
+
+  // the nonce of the sender is not zero
+  let client = SignerMiddleware::new(provider.clone(), sender.clone());
+  let tx = TransactionRequest::new().to(get_random_wallet().address()).value(1);
+  client.send_transaction(tx, None).await?.await?;
+
+  let nonce = provider.get_transaction_count(sender.address(), None).await?;
+  let balance = provider.get_balance(sender.address(), None).await?;
+
+  let tx_type = if invalid.tx_type() == 2 { "eip1559" } else { "legacy" };
+  let mut tx = prepare_tx(tx_type, U256::zero(), nonce, sender.address(), sender.clone(), get_chain_id());
+  tx.set_from(sender.address());
+  tx.set_nonce(nonce);
+  tx.set_chain_id(get_chain_id());
+  provider.fill_transaction(&mut tx, None).await?;
+  let base_fee = provider.get_block(BlockNumber::Latest).await?.and_then(|block| block.base_fee_per_gas);
+
+  let rlp = mutate(tx, &sender, invalid, balance, base_fee);
+  let error = match provider.send_raw_transaction(rlp.into()).await {
+    Ok(_) => panic!("{:?} transaction must be rejected by {}", invalid, provider_name),
+    Err(e) => e.to_string(),
+  };
+  println!("[ Invalid ] - {} {:?}: {}", provider_name, invalid, error);
+
+  let target = if provider_name == "geth" { Target::Geth } else { Target::Proxy };
+  let expected = invalid.error(target).unwrap();
+  assert!(error.contains(expected), "{:?}: expected error '{}', got {}", invalid, expected, error);
+  assert_eq!(provider.get_transaction_count(sender.address(), None).await?, nonce);
+  assert_eq!(provider.get_balance(sender.address(), None).await?, balance);
+  check_state(vec![sender.address()]).await;
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// Regression cases of the differential bytecode fuzzer, see fuzz::bytecode_fuzz
+#[rstest]
+#[serial]
+async fn bytecode_regressions() -> Result<(), Box<dyn std::error::Error>> {
+  let total_start = Instant::now();
+
+  let sender = get_random_wallet();
+  let _ = airdrop_to_address(sender.address(), U256::exp10(18), "proxy").await?; // fund sender
+
+  let mut failures = vec![];
+  for regression in regressions(REGRESSIONS) {
+    println!("[ Info: ] - Regression {}", regression.name);
+    let (address, recipt) = execute(&regression.code, &regression.calldata, &sender).await?;
+    if !compare(address, &regression.calldata, &recipt).await.is_empty() {
+      failures.push(regression.name);
+    }
+  }
+  assert!(failures.is_empty(), "regressions differ from geth: {:?}", failures);
+
+  println!(
+      "[ {:.2}s ] - Total duration\n",
+      total_start.elapsed().as_secs_f64()
+  );
+  Ok(())
+}
+
+/// Offline tests of the receipt comparator, see shared::receipt
+#[test]
+fn receipt_approx_tolerance() {
+  assert!(receipt::approx(100.into(), 100.into(), 0.0));
+  assert!(!receipt::approx(100.into(), 99.into(), 0.0));
+  assert!(receipt::approx(100.into(), 99.into(), 0.01));
+  assert!(receipt::approx(99.into(), 100.into(), 0.01));
+  assert!(!receipt::approx(100.into(), 98.into(), 0.01));
+  assert!(receipt::approx(U256::zero(), U256::zero(), 0.0));
+  assert!(receipt::approx(U256::MAX, U256::MAX - 1, 0.01));
+}
+
+#[test]
+fn receipt_number_formats() {
+  assert_eq!(receipt::number(&json!("0x1f")), Some(31.into()));
+  assert_eq!(receipt::number(&json!("31")), Some(31.into()));
+  assert_eq!(receipt::number(&json!(31)), Some(31.into()));
+  assert_eq!(receipt::number(&json!("0xzz")), None);
+  assert_eq!(receipt::number(&json!([1])), None);
+}
+
+#[test]
+fn receipt_policy_resolution() {
+  let strict = ReceiptComparator::strict();
+  assert_eq!(strict.policy_of("l1Fee"), FieldPolicy::Equal);
+  assert_eq!(strict.policy_of("gasUsed"), FieldPolicy::Equal);
+
+  let op_stack = ReceiptComparator::op_stack();
+  for field in receipt::OP_STACK_L1_FIELDS {
+    assert_eq!(op_stack.policy_of(field), FieldPolicy::Ignore);
+  }
+  assert_eq!(op_stack.policy_of("gasUsed"), FieldPolicy::Equal);
+
+  let custom = ReceiptComparator::op_stack()
+    .default_policy(FieldPolicy::Ignore)
+    .policy("gasUsed", FieldPolicy::Approx(0.1));
+  assert_eq!(custom.policy_of("gasUsed"), FieldPolicy::Approx(0.1));
+  assert_eq!(custom.policy_of("l1Fee"), FieldPolicy::Ignore);
+  assert_eq!(custom.policy_of("status"), FieldPolicy::Ignore);
+}
+
+#[test]
+fn receipt_compare_gas_used_approx() {
+  let proxy = TransactionReceipt { gas_used: Some(1000.into()), ..Default::default() };
+  let geth = TransactionReceipt { gas_used: Some(1050.into()), ..Default::default() };
+
+  assert_eq!(ReceiptComparator::strict().compare(&proxy, &geth).len(), 1);
+  let approx = ReceiptComparator::strict().policy("gasUsed", FieldPolicy::Approx(0.05));
+  assert!(approx.compare(&proxy, &geth).is_empty());
+  let tight = ReceiptComparator::strict().policy("gasUsed", FieldPolicy::Approx(0.01));
+  assert_eq!(tight.compare(&proxy, &geth).len(), 1);
+
+  // the field missing on one side differs under any policy but Ignore
+  let missing = TransactionReceipt { gas_used: None, ..Default::default() };
+  assert_eq!(approx.compare(&proxy, &missing).len(), 1);
+  let ignore = ReceiptComparator::strict().policy("gasUsed", FieldPolicy::Ignore);
+  assert!(ignore.compare(&proxy, &missing).is_empty());
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+contract Create2Child {
+    uint public val = 1;
+
+    function get() public view returns(uint) {
+        return val;
+    }
+
+    function destruct() public {
+        selfdestruct(payable(msg.sender));
+    }
+}
+
+contract Create2Factory {
+    address public last;
+
+    event Deployed(address addr, bytes32 salt);
+
+    function deploy(bytes32 salt) public returns (address) {
+        Create2Child child = new Create2Child{salt: salt}();
+        require(child.get() == 1);
+        last = address(child);
+        emit Deployed(last, salt);
+        return last;
+    }
+
+    // the child is created and destroyed in the same transaction, EIP-6780 removes it
+    function deploy_and_destruct(bytes32 salt) public {
+        Create2Child child = new Create2Child{salt: salt}();
+        last = address(child);
+        child.destruct();
+    }
+
+    function calc(bytes32 salt) public view returns (address) {
+        bytes32 hash = keccak256(
+            abi.encodePacked(bytes1(0xff), address(this), salt, keccak256(type(Create2Child).creationCode))
+        );
+        return address(uint160(uint(hash)));
+    }
+}
@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: MIT
+pragma solidity <=0.8.28;
+
+contract EmulationProbe {
+    uint public value = 7;
+    uint public counter = 0;
+
+    event Set(address indexed sender, uint value);
+
+    function set(uint x) public returns (uint) {
+        value = x;
+        emit Set(msg.sender, x);
+        return x;
+    }
+
+    function get() public view returns (uint) {
+        return value;
+    }
+
+    function iterate() public returns (uint) {
+        uint i = 0;
+
+        while (i < 200) {
+            counter = counter + 1;
+            i = i + 1;
+        }
+        emit Set(msg.sender, counter);
+        return counter;
+    }
+}
@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+// Every contract writes the results of the opcode group to values[i] = slot i,
+// the slots are compared between proxy and geth
+
+contract OpBlockContext {
+    uint256[16] public values;
+
+    function run() public {
+        values[0] = block.number;
+        values[1] = block.timestamp;
+        values[2] = block.basefee;
+        values[3] = block.prevrandao;
+        values[4] = block.chainid;
+        values[5] = uint256(uint160(address(block.coinbase)));
+        values[6] = uint256(blockhash(block.number - 1));
+        values[7] = uint256(blockhash(block.number));
+        values[8] = block.number > 257 ? uint256(blockhash(block.number - 257)) : 0;
+        values[9] = block.gaslimit;
+    }
+}
+
+contract OpEnvironment {
+    uint256[16] public values;
+
+    function run(address eoa) public payable {
+        bytes32 prefix;
+        address self = address(this);
+        assembly {
+            extcodecopy(self, 0, 0, 32)
+            prefix := mload(0)
+        }
+
+        values[0] = uint256(uint160(tx.origin));
+        values[1] = tx.gasprice;
+        values[2] = uint256(uint160(msg.sender));
+        values[3] = msg.value;
+        values[4] = uint256(address(this).codehash);
+        values[5] = address(this).code.length;
+        values[6] = uint256(prefix);
+        values[7] = uint256(eoa.codehash);
+        values[8] = eoa.code.length;
+        // account that does not exist
+        values[9] = uint256(address(uint160(0xdead0001)).codehash);
+        values[10] = address(this).balance;
+    }
+}
+
+contract OpMemory {
+    uint256[16] public values;
+
+    function run() public {
+        uint256 size1;
+        uint256 gas1;
+        uint256 size2;
+        uint256 gas2;
+        uint256 size3;
+        uint256 gas3;
+        assembly {
+            gas1 := gas()
+            mstore(0x400, 1)
+            size1 := msize()
+            gas1 := sub(gas1, gas())
+
+            gas2 := gas()
+            mstore(0x8000, 1)
+            size2 := msize()
+            gas2 := sub(gas2, gas())
+
+            gas3 := gas()
+            let x := mload(0x10000)
+            size3 := msize()
+            gas3 := sub(gas3, gas())
+        }
+
+        values[0] = size1;
+        values[1] = gas1;
+        values[2] = size2;
+        values[3] = gas2;
+        values[4] = size3;
+        values[5] = gas3;
+    }
+}
+
+contract OpReturnData {
+    uint256[16] public values;
+
+    function ret32() external pure returns (uint256) {
+        return 42;
+    }
+
+    // offset + size is above RETURNDATASIZE, the frame halts
+    function copy_out_of_bounds() external view {
+        this.ret32();
+        assembly {
+            returndatacopy(0, 1, 32)
+        }
+    }
+
+    // zero bytes at the end of the return data
+    function copy_at_end() external view {
+        this.ret32();
+        assembly {
+            returndatacopy(0, 32, 0)
+        }
+    }
+
+    function run() public {
+        uint256 size;
+        uint256 copied;
+        this.ret32();
+        assembly {
+            size := returndatasize()
+            returndatacopy(0, 0, 32)
+            copied := mload(0)
+        }
+        values[0] = size;
+        values[1] = copied;
+
+        (bool success, ) = address(this).call(abi.encodeWithSignature("copy_at_end()"));
+        values[3] = success ? 1 : 0;
+        // the failed call is the last one before RETURNDATASIZE
+        (success, ) = address(this).call(abi.encodeWithSignature("copy_out_of_bounds()"));
+        values[2] = success ? 1 : 0;
+
+        // the failed call leaves the empty return data
+        assembly {
+            size := returndatasize()
+        }
+        values[4] = size;
+    }
+}
+
+// solc 0.8.28 emits PUSH0 for the zero constants of this contract
+contract OpPush0Mcopy {
+    uint256[16] public values;
+
+    function run() public {
+        uint256 copied;
+        uint256 overlap_forward;
+        uint256 overlap_backward;
+        uint256 size;
+        assembly {
+            mstore(0x100, 0x0102030405060708091011121314151617181920212223242526272829303132)
+            mcopy(0x200, 0x100, 32)
+            copied := mload(0x200)
+
+            mcopy(0x101, 0x100, 31)
+            overlap_forward := mload(0x100)
+
+            mstore(0x300, 0x0102030405060708091011121314151617181920212223242526272829303132)
+            mcopy(0x300, 0x301, 31)
+            overlap_backward := mload(0x300)
+
+            // memory is expanded by the destination
+            mcopy(0x1000, 0x100, 1)
+            size := msize()
+        }
+
+        values[0] = copied;
+        values[1] = overlap_forward;
+        values[2] = overlap_backward;
+        values[3] = size;
+    }
+}
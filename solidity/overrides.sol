@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+import "./hello_world.sol";
+
+// View functions of the eth_call state and block override tests
+contract OverrideProbe {
+    uint256 public value;
+    uint256 public other;
+
+    constructor() {
+        value = 1;
+        other = 2;
+    }
+
+    function balance_of(address account) public view returns (uint256) {
+        return account.balance;
+    }
+
+    function code_size(address account) public view returns (uint256) {
+        return account.code.length;
+    }
+
+    // the address depends on the nonce of the contract
+    function next_address() public returns (address) {
+        return address(new HelloWorld());
+    }
+
+    function block_context() public view returns (uint256, uint256, uint256, address, uint256, uint256) {
+        return (block.number, block.timestamp, block.gaslimit, block.coinbase, block.prevrandao, block.basefee);
+    }
+}
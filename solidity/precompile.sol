@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+contract PrecompileProbe {
+    // gas: gas used by the staticcall, including the constant call overhead
+    event Called(address precompile, bool success, uint256 gas, bytes output);
+
+    function probe(address precompile, bytes memory input) public returns (bool, bytes memory, uint256) {
+        bool success;
+        uint256 used;
+        // the output is not copied by the call, so the overhead does not depend on the output size
+        assembly {
+            let before := gas()
+            success := staticcall(gas(), precompile, add(input, 32), mload(input), 0, 0)
+            used := sub(before, gas())
+        }
+
+        bytes memory output = new bytes(returndatasize());
+        assembly {
+            returndatacopy(add(output, 32), 0, returndatasize())
+        }
+
+        emit Called(precompile, success, used, output);
+        return (success, output, used);
+    }
+}
@@ -15,24 +15,29 @@ contract  Destruct{
 }
 
 contract DestructCaller {
-    address payable  addr1;
-    address payable  addr2;
+    address payable public addr1;
+    address payable public addr2;
 
     constructor() payable {}
 
     function deploy() public payable {
-        Destruct a = new Destruct{value: 0}();
+        Destruct a = new Destruct{value: msg.value}();
         addr1 = payable (address(a));
         require(a.get() == 1);
     }
 
     function deploy_and_destruct() public payable {
-        Destruct a = new Destruct{value: 0}();
+        Destruct a = new Destruct{value: msg.value}();
         addr2 = payable (address(a));
         require(a.get() == 1);
         a.destruct();
     }
 
+    // selfdestruct in a transaction other than the creating one, EIP-6780 keeps the account
+    function destruct_first() public {
+        Destruct(addr1).destruct();
+    }
+
     function check() public  {
         Destruct a1 = Destruct(addr1);
         require(a1.get() == 1);
@@ -21,3 +21,61 @@ contract TestTransientStorage {
         require(loadedValue == value);
     }
 }
+
+contract TransientProbe {
+    // step of the nested call and the transient value observed at this step
+    event Loaded(uint256 step, uint256 value);
+
+    function tstore_(uint256 value) private {
+        assembly {
+            tstore(1, value)
+        }
+    }
+
+    function tload_() private view returns (uint256 value) {
+        assembly {
+            value := tload(1)
+        }
+    }
+
+    function get() public view returns (uint256) {
+        return tload_();
+    }
+
+    function load() public returns (uint256) {
+        uint256 value = tload_();
+        emit Loaded(0, value);
+        return value;
+    }
+
+    function set(uint256 value) public returns (uint256) {
+        tstore_(value);
+        emit Loaded(0, tload_());
+        return tload_();
+    }
+
+    function store(uint256 value) external {
+        tstore_(value);
+    }
+
+    function store_and_revert(uint256 value) external {
+        tstore_(value);
+        revert("reverted");
+    }
+
+    function nested(uint256 value) public returns (uint256) {
+        tstore_(value);
+        // visible in the internal call
+        emit Loaded(1, this.get());
+
+        // written by the internal call
+        this.store(value + 1);
+        emit Loaded(2, tload_());
+
+        // the write of the reverted call is discarded
+        try this.store_and_revert(value + 2) {} catch {}
+        emit Loaded(3, tload_());
+
+        return tload_();
+    }
+}